@@ -0,0 +1,116 @@
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+#[derive(Copy, Clone, Debug)]
+pub struct Aabb {
+    pub minimum: Point3,
+    pub maximum: Point3,
+}
+
+impl Aabb {
+    pub fn new(minimum: Point3, maximum: Point3) -> Self {
+        Self { minimum, maximum }
+    }
+
+    pub fn hit(&self, r: &Ray, t_min: f32, t_max: f32) -> bool {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+        for a in 0..3 {
+            let inv_d = 1. / axis(r.direction, a);
+            let mut t0 = (axis(self.minimum, a) - axis(r.origin, a)) * inv_d;
+            let mut t1 = (axis(self.maximum, a) - axis(r.origin, a)) * inv_d;
+            if inv_d < 0. {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max <= t_min {
+                return false;
+            }
+        }
+        true
+    }
+
+    // The box spanned by two opposite corners given in any order.
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        Self {
+            minimum: Point3 {
+                x: a.x.min(b.x),
+                y: a.y.min(b.y),
+                z: a.z.min(b.z),
+            },
+            maximum: Point3 {
+                x: a.x.max(b.x),
+                y: a.y.max(b.y),
+                z: a.z.max(b.z),
+            },
+        }
+    }
+
+    pub fn surrounding_box(box0: Aabb, box1: Aabb) -> Aabb {
+        Aabb {
+            minimum: Point3 {
+                x: box0.minimum.x.min(box1.minimum.x),
+                y: box0.minimum.y.min(box1.minimum.y),
+                z: box0.minimum.z.min(box1.minimum.z),
+            },
+            maximum: Point3 {
+                x: box0.maximum.x.max(box1.maximum.x),
+                y: box0.maximum.y.max(box1.maximum.y),
+                z: box0.maximum.z.max(box1.maximum.z),
+            },
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        (self.minimum + self.maximum) / Vec3::new(2.)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d = self.maximum - self.minimum;
+        2. * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    // Widens every side thinner than `delta`, so the slab test still finds
+    // flat objects such as axis aligned quads. `delta` is relative to the
+    // largest coordinate, f32 runs out of precision quickly far from the
+    // origin.
+    pub fn pad(&self, delta: f32) -> Aabb {
+        let largest = [self.minimum, self.maximum]
+            .iter()
+            .flat_map(|p| [p.x, p.y, p.z])
+            .fold(1f32, |m, c| m.max(c.abs()));
+        let delta = delta * largest;
+        let widen = |min: f32, max: f32| {
+            if max - min >= delta {
+                (min, max)
+            } else {
+                let mid = 0.5 * (min + max);
+                (mid - 0.5 * delta, mid + 0.5 * delta)
+            }
+        };
+        let (x0, x1) = widen(self.minimum.x, self.maximum.x);
+        let (y0, y1) = widen(self.minimum.y, self.maximum.y);
+        let (z0, z1) = widen(self.minimum.z, self.maximum.z);
+        Aabb {
+            minimum: Point3 {
+                x: x0,
+                y: y0,
+                z: z0,
+            },
+            maximum: Point3 {
+                x: x1,
+                y: y1,
+                z: z1,
+            },
+        }
+    }
+}
+
+pub fn axis(v: Vec3, a: usize) -> f32 {
+    match a {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
@@ -0,0 +1,323 @@
+use crate::aabb::{axis, Aabb};
+use crate::hittable::{HitRecord, Hittable};
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::Vec3;
+use std::sync::Arc;
+
+const BUCKETS: usize = 12;
+const MAX_LEAF_SIZE: usize = 4;
+const TRAVERSAL_COST: f32 = 0.125;
+// Past this depth nodes are split at the median instead, so a lopsided SAH
+// tree still fits the fixed traversal stack: 32 levels plus one per halving
+// of up to 2^31 objects.
+const SAH_DEPTH: usize = 32;
+const STACK_SIZE: usize = 64;
+
+// Bounding volume hierarchy over a list of hittables, split with a binned
+// surface area heuristic. A hit reports the index of the object in the list
+// it was built from as `material_index`, so the world list can still be used
+// to look up materials. The boxes hold moving objects for rays timed between
+// `time0` and `time1` given to `new`; rays outside that may miss them.
+#[derive(Clone)]
+pub struct Bvh {
+    objects: Vec<Arc<dyn Hittable + Sync + Send>>,
+    nodes: Vec<BvhNode>,
+    indices: Vec<usize>,
+    unbounded: Vec<usize>,
+    time0: f32,
+    time1: f32,
+}
+
+#[derive(Copy, Clone)]
+struct BvhNode {
+    bbox: Aabb,
+    // leaves: first entry in `indices`; interior nodes: index of the right child
+    // (the left child always follows its parent)
+    offset: usize,
+    count: usize,
+    axis: usize,
+}
+
+#[derive(Copy, Clone)]
+struct BuildEntry {
+    index: usize,
+    bbox: Aabb,
+    centroid: Vec3,
+}
+
+impl Bvh {
+    pub fn new(objects: Vec<Arc<dyn Hittable + Sync + Send>>, time0: f32, time1: f32) -> Self {
+        let mut entries = vec![];
+        let mut unbounded = vec![];
+
+        for (i, object) in objects.iter().enumerate() {
+            let mut bbox = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+            if object.bounding_box(time0, time1, &mut bbox) {
+                entries.push(BuildEntry {
+                    index: i,
+                    bbox,
+                    centroid: bbox.centroid(),
+                });
+            } else {
+                unbounded.push(i);
+            }
+        }
+
+        let mut bvh = Self {
+            objects,
+            nodes: Vec::with_capacity(2 * entries.len()),
+            indices: Vec::with_capacity(entries.len()),
+            unbounded,
+            time0,
+            time1,
+        };
+
+        if !entries.is_empty() {
+            bvh.build(&mut entries, 0);
+        }
+        bvh
+    }
+
+    fn build(&mut self, entries: &mut [BuildEntry], depth: usize) -> usize {
+        let node_index = self.nodes.len();
+        let bbox = entries
+            .iter()
+            .skip(1)
+            .fold(entries[0].bbox, |acc, e| Aabb::surrounding_box(acc, e.bbox));
+
+        self.nodes.push(BvhNode {
+            bbox,
+            offset: 0,
+            count: 0,
+            axis: 0,
+        });
+
+        let halves = if depth < SAH_DEPTH {
+            split(entries, bbox)
+        } else {
+            split_median(entries)
+        };
+        match halves {
+            Some((split_axis, mid)) => {
+                let (left, right) = entries.split_at_mut(mid);
+                self.build(left, depth + 1);
+                let right_index = self.build(right, depth + 1);
+                let node = &mut self.nodes[node_index];
+                node.offset = right_index;
+                node.axis = split_axis;
+            }
+            None => {
+                let node = &mut self.nodes[node_index];
+                node.offset = self.indices.len();
+                node.count = entries.len();
+                self.indices.extend(entries.iter().map(|e| e.index));
+            }
+        }
+
+        node_index
+    }
+}
+
+// Picks the cheapest bucket boundary along any axis and partitions the
+// entries around it. Returns the split axis and the partition point, or
+// None when a leaf is cheaper.
+fn split(entries: &mut [BuildEntry], bbox: Aabb) -> Option<(usize, usize)> {
+    let n = entries.len();
+    if n == 1 {
+        return None;
+    }
+
+    let centroid_bounds = entries.iter().skip(1).fold(
+        Aabb::new(entries[0].centroid, entries[0].centroid),
+        |acc, e| Aabb::surrounding_box(acc, Aabb::new(e.centroid, e.centroid)),
+    );
+
+    let mut best: Option<(usize, usize, f32)> = None;
+    for a in 0..3 {
+        let lo = axis(centroid_bounds.minimum, a);
+        let hi = axis(centroid_bounds.maximum, a);
+        if hi - lo <= f32::EPSILON {
+            continue;
+        }
+
+        let mut counts = [0usize; BUCKETS];
+        let mut boxes: [Option<Aabb>; BUCKETS] = [None; BUCKETS];
+        for e in entries.iter() {
+            let b = bucket(axis(e.centroid, a), lo, hi);
+            counts[b] += 1;
+            boxes[b] = Some(match boxes[b] {
+                Some(acc) => Aabb::surrounding_box(acc, e.bbox),
+                None => e.bbox,
+            });
+        }
+
+        for boundary in 1..BUCKETS {
+            let (left_count, left_area) = sweep(&counts[..boundary], &boxes[..boundary]);
+            let (right_count, right_area) = sweep(&counts[boundary..], &boxes[boundary..]);
+            if left_count == 0 || right_count == 0 {
+                continue;
+            }
+
+            let cost = TRAVERSAL_COST
+                + (left_count as f32 * left_area + right_count as f32 * right_area)
+                    / bbox.surface_area().max(f32::MIN_POSITIVE);
+            if best.is_none_or(|(_, _, c)| cost < c) {
+                best = Some((a, boundary, cost));
+            }
+        }
+    }
+
+    match best {
+        Some((a, boundary, cost)) if cost < n as f32 || n > MAX_LEAF_SIZE => {
+            let lo = axis(centroid_bounds.minimum, a);
+            let hi = axis(centroid_bounds.maximum, a);
+            let mid = partition(entries, |e| bucket(axis(e.centroid, a), lo, hi) < boundary);
+            Some((a, mid))
+        }
+        Some(_) => None,
+        None if n > MAX_LEAF_SIZE => {
+            // every centroid coincides; split down the middle so leaves stay small
+            Some((0, n / 2))
+        }
+        None => None,
+    }
+}
+
+// Halves the entries around the median centroid on the widest axis, or None
+// when they fit in a leaf.
+fn split_median(entries: &mut [BuildEntry]) -> Option<(usize, usize)> {
+    let n = entries.len();
+    if n <= MAX_LEAF_SIZE {
+        return None;
+    }
+
+    let centroid_bounds = entries.iter().skip(1).fold(
+        Aabb::new(entries[0].centroid, entries[0].centroid),
+        |acc, e| Aabb::surrounding_box(acc, Aabb::new(e.centroid, e.centroid)),
+    );
+    let extent = centroid_bounds.maximum - centroid_bounds.minimum;
+    let a = (0..3)
+        .max_by(|&i, &j| axis(extent, i).total_cmp(&axis(extent, j)))
+        .unwrap();
+    entries.select_nth_unstable_by(n / 2, |l, r| {
+        axis(l.centroid, a).total_cmp(&axis(r.centroid, a))
+    });
+    Some((a, n / 2))
+}
+
+fn bucket(c: f32, lo: f32, hi: f32) -> usize {
+    (((c - lo) / (hi - lo) * BUCKETS as f32) as usize).min(BUCKETS - 1)
+}
+
+fn sweep(counts: &[usize], boxes: &[Option<Aabb>]) -> (usize, f32) {
+    let count = counts.iter().sum();
+    let bbox = boxes.iter().flatten().fold(None, |acc: Option<Aabb>, b| {
+        Some(match acc {
+            Some(acc) => Aabb::surrounding_box(acc, *b),
+            None => *b,
+        })
+    });
+    (count, bbox.map_or(0., |b| b.surface_area()))
+}
+
+fn partition<F>(entries: &mut [BuildEntry], pred: F) -> usize
+where
+    F: Fn(&BuildEntry) -> bool,
+{
+    let mut mid = 0;
+    for i in 0..entries.len() {
+        if pred(&entries[i]) {
+            entries.swap(i, mid);
+            mid += 1;
+        }
+    }
+    mid
+}
+
+impl Hittable for Bvh {
+    fn hit(
+        &self,
+        r: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        let mut hit_anything = false;
+        let mut closest_so_far = max;
+
+        let mut temp_rec = *rec_out;
+
+        for &i in &self.unbounded {
+            if self.objects[i].hit(r, min, closest_so_far, &mut temp_rec, sampler) {
+                hit_anything = true;
+                closest_so_far = temp_rec.t;
+                *rec_out = temp_rec;
+                rec_out.material_index = i as i32;
+            }
+        }
+
+        if self.nodes.is_empty() {
+            return hit_anything;
+        }
+
+        let negative = [r.direction.x < 0., r.direction.y < 0., r.direction.z < 0.];
+        // the build keeps the tree shallow enough that this never overflows
+        let mut stack = [0usize; STACK_SIZE];
+        let mut top = 1;
+
+        while top > 0 {
+            top -= 1;
+            let node_index = stack[top];
+            let node = &self.nodes[node_index];
+            if !node.bbox.hit(r, min, closest_so_far) {
+                continue;
+            }
+
+            if node.count > 0 {
+                for &i in &self.indices[node.offset..node.offset + node.count] {
+                    if self.objects[i].hit(r, min, closest_so_far, &mut temp_rec, sampler) {
+                        hit_anything = true;
+                        closest_so_far = temp_rec.t;
+                        *rec_out = temp_rec;
+                        rec_out.material_index = i as i32;
+                    }
+                }
+            } else if negative[node.axis] {
+                // visit the nearer child first so the far one is culled more often
+                stack[top] = node_index + 1;
+                stack[top + 1] = node.offset;
+                top += 2;
+            } else {
+                stack[top] = node.offset;
+                stack[top + 1] = node_index + 1;
+                top += 2;
+            }
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        if self.nodes.is_empty() || !self.unbounded.is_empty() {
+            return false;
+        }
+        if (time0, time1) == (self.time0, self.time1) {
+            *output_box = self.nodes[0].bbox;
+            return true;
+        }
+
+        // the root box only holds for the interval it was built over
+        let mut bounds: Option<Aabb> = None;
+        for object in &self.objects {
+            let mut bbox = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+            if !object.bounding_box(time0, time1, &mut bbox) {
+                return false;
+            }
+            bounds = Some(bounds.map_or(bbox, |acc| Aabb::surrounding_box(acc, bbox)));
+        }
+        *output_box = bounds.unwrap();
+        true
+    }
+}
@@ -1,14 +1,22 @@
 use crate::random_in_unit_sphere;
 use crate::ray::Ray;
+use crate::sampler::Sampler;
 use crate::vec3::{Math, Point3, Vec3};
 use rand::prelude::*;
 
+#[allow(dead_code)]
 #[derive(Copy, Clone, Debug)]
 pub struct Camera {
     pub aspect_ratio: f32,
     pub viewport_height: f32,
     pub viewport_width: f32,
 
+    lookfrom: Point3,
+    lookat: Point3,
+    vup: Vec3,
+    vfov: f32,
+    aperture: f32,
+    focus_dist: f32,
     origin: Point3,
     lower_left_corner: Point3,
     horizontal: Vec3,
@@ -17,12 +25,13 @@ pub struct Camera {
     u: Vec3,
     v: Vec3,
     w: Vec3,
-    _time0: f32,
-    _time1: f32,
+    // shutter open and close, the span ray times are drawn from
+    time0: f32,
+    time1: f32,
 }
 
 pub trait CameraProperties {
-    fn get_ray(self, u: f32, v: f32) -> Ray;
+    fn get_ray(self, u: f32, v: f32, sampler: &mut Sampler) -> Ray;
 }
 
 impl Camera {
@@ -56,28 +65,81 @@ impl Camera {
         let lens_radius = aperture / 2.;
 
         Self {
-            aspect_ratio: aspect_ratio,
-            viewport_height: viewport_height,
-            viewport_width: viewport_width,
-            origin: origin,
-            horizontal: horizontal,
-            vertical: vertical,
-            lower_left_corner: lower_left_corner,
-            lens_radius: lens_radius,
-            u: u,
-            v: v,
-            w: w,
-            _time0: 0.,
-            _time1: 0.,
+            aspect_ratio,
+            viewport_height,
+            viewport_width,
+            lookfrom,
+            lookat,
+            vup,
+            vfov,
+            aperture,
+            focus_dist,
+            origin,
+            horizontal,
+            vertical,
+            lower_left_corner,
+            lens_radius,
+            u,
+            v,
+            w,
+            time0: 0.,
+            time1: 1.,
         }
     }
+
+    // Open for the interval [open, close); equal times freeze all motion.
+    pub fn with_shutter(&self, open: f32, close: f32) -> Self {
+        Self {
+            time0: open,
+            time1: close,
+            ..*self
+        }
+    }
+
+    pub fn shutter(&self) -> (f32, f32) {
+        (self.time0, self.time1)
+    }
+
+    pub fn lookfrom(&self) -> Point3 {
+        self.lookfrom
+    }
+
+    pub fn lookat(&self) -> Point3 {
+        self.lookat
+    }
+
+    pub fn with_aspect_ratio(&self, aspect_ratio: f32) -> Self {
+        Camera::new(
+            self.lookfrom,
+            self.lookat,
+            self.vup,
+            self.vfov,
+            aspect_ratio,
+            self.aperture,
+            self.focus_dist,
+        )
+        .with_shutter(self.time0, self.time1)
+    }
+
+    // Same lens and field of view, moved to a new viewpoint.
+    pub fn with_view(&self, lookfrom: Point3, lookat: Point3) -> Self {
+        Camera::new(
+            lookfrom,
+            lookat,
+            self.vup,
+            self.vfov,
+            self.aspect_ratio,
+            self.aperture,
+            self.focus_dist,
+        )
+        .with_shutter(self.time0, self.time1)
+    }
 }
 
 impl CameraProperties for Camera {
-    fn get_ray(self, s: f32, t: f32) -> Ray {
-        let rd = Vec3::new(self.lens_radius) * random_in_unit_sphere();
+    fn get_ray(self, s: f32, t: f32, sampler: &mut Sampler) -> Ray {
+        let rd = Vec3::new(self.lens_radius) * random_in_unit_sphere(sampler);
         let offset = self.u * Vec3::new(rd.x) + self.v * Vec3::new(rd.y);
-        let mut rng = rand::thread_rng();
 
         Ray {
             origin: self.origin + offset,
@@ -86,7 +148,7 @@ impl CameraProperties for Camera {
                 + Vec3::new(t) * self.vertical
                 - self.origin
                 - offset,
-            time: rng.gen_range(0. ..1.),
+            time: self.time0 + sampler.gen_range(0. ..1.) * (self.time1 - self.time0),
         }
     }
 }
@@ -0,0 +1,255 @@
+use rust_renderer::scenes;
+use rust_renderer::tonemap::{Operator, OPERATOR_NAMES};
+use rust_renderer::vec3::Vec3;
+use rust_renderer::RenderSettings;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Only the flags that were given are set, so they can be layered on top of
+// the defaults or a scene file's settings.
+#[derive(Default)]
+pub struct Options {
+    pub width: Option<u32>,
+    pub aspect_ratio: Option<f32>,
+    pub samples: Option<u32>,
+    pub depth: Option<i32>,
+    pub threads: Option<usize>,
+    pub tile_size: Option<u32>,
+    pub seed: Option<u64>,
+    pub tonemap: Option<Operator>,
+    pub exposure: Option<f32>,
+    pub white: Option<f32>,
+    pub scene: String,
+    pub output: String,
+    pub lookfrom: Option<Vec3>,
+    pub lookat: Option<Vec3>,
+    pub shutter: Option<(f32, f32)>,
+}
+
+impl Options {
+    pub fn apply(&self, settings: &mut RenderSettings, aspect_ratio: &mut f32) {
+        if let Some(width) = self.width {
+            settings.image_width = width;
+        }
+        if let Some(ratio) = self.aspect_ratio {
+            *aspect_ratio = ratio;
+        }
+        if let Some(samples) = self.samples {
+            settings.samples_per_pixel = samples;
+        }
+        if let Some(depth) = self.depth {
+            settings.max_depth = depth;
+        }
+        if let Some(threads) = self.threads {
+            settings.threads = threads;
+        }
+        if let Some(tile_size) = self.tile_size {
+            settings.tile_size = tile_size;
+        }
+        if let Some(seed) = self.seed {
+            settings.seed = seed;
+        }
+        if let Some(operator) = self.tonemap {
+            settings.tone_mapping.operator = operator;
+        }
+        if let Some(exposure) = self.exposure {
+            settings.tone_mapping.exposure = exposure;
+        }
+        if let Some(white) = self.white {
+            settings.tone_mapping.white = white;
+        }
+        settings.image_height = (settings.image_width as f32 / *aspect_ratio) as u32;
+    }
+}
+
+pub enum Command {
+    Render(Options),
+    Help,
+}
+
+pub fn usage() -> String {
+    format!(
+        "usage: rust-renderer [options]
+
+options:
+  -w, --width <pixels>        image width (default 600)
+  -a, --aspect-ratio <ratio>  width / height, as a number or w:h (default 3:2)
+  -s, --samples <count>       samples per pixel (default 50)
+  -d, --depth <bounces>       maximum ray depth (default 50)
+  -t, --threads <count>       worker threads (default: number of cpus)
+      --tile-size <pixels>    edge length of a render tile (default 16)
+      --seed <number>         random seed (default 0)
+      --tonemap <operator>    tone mapping for 8 bit output: {}
+                              (default clamp)
+      --exposure <stops>      exposure adjustment before tone mapping (default 0)
+      --white <luminance>     white point for extended-reinhard (default 4)
+      --scene <name|file>     scene to render, a .toml scene file, a .gltf or
+                              .glb model or one of:
+                              {} (default random-spheres)
+      --lookfrom <x,y,z>      camera position, overrides the scene's
+      --lookat <x,y,z>        camera target, overrides the scene's
+      --shutter <open,close>  times the shutter is open between, overrides
+                              the scene's (default 0,1)
+  -o, --output <file>         output image, .exr, .hdr and .pfm keep the full
+                              float range (default render.png)
+  -h, --help                  print this help",
+        OPERATOR_NAMES.join(", "),
+        scenes::NAMES.join(", ")
+    )
+}
+
+pub fn parse<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = Options {
+        scene: String::from("random-spheres"),
+        output: String::from("render.png"),
+        ..Options::default()
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        if flag == "-h" || flag == "--help" {
+            return Ok(Command::Help);
+        }
+
+        let value = match inline {
+            Some(value) => value.to_string(),
+            None => args
+                .next()
+                .ok_or_else(|| format!("missing value for {}", flag))?,
+        };
+
+        match flag.as_str() {
+            "-w" | "--width" => options.width = Some(parse_number(&flag, &value)?),
+            "-a" | "--aspect-ratio" => options.aspect_ratio = Some(parse_aspect_ratio(&value)?),
+            "-s" | "--samples" => options.samples = Some(parse_number(&flag, &value)?),
+            "-d" | "--depth" => options.depth = Some(parse_number(&flag, &value)?),
+            "-t" | "--threads" => options.threads = Some(parse_number(&flag, &value)?),
+            "--tile-size" => options.tile_size = Some(parse_number(&flag, &value)?),
+            "--seed" => options.seed = Some(parse_number(&flag, &value)?),
+            "--tonemap" => options.tonemap = Some(value.parse()?),
+            "--exposure" => options.exposure = Some(parse_number(&flag, &value)?),
+            "--white" => options.white = Some(parse_number(&flag, &value)?),
+            "--scene" => options.scene = value,
+            "--lookfrom" => options.lookfrom = Some(parse_vec3(&flag, &value)?),
+            "--lookat" => options.lookat = Some(parse_vec3(&flag, &value)?),
+            "--shutter" => options.shutter = Some(parse_shutter(&value)?),
+            "-o" | "--output" => options.output = value,
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+
+    if !is_scene_file(&options.scene) && !scenes::NAMES.contains(&options.scene.as_str()) {
+        return Err(format!(
+            "unknown scene {}, expected a .toml, .gltf or .glb file or one of: {}",
+            options.scene,
+            scenes::NAMES.join(", ")
+        ));
+    }
+    if options.output.is_empty() {
+        return Err(String::from("--output must not be empty"));
+    }
+
+    Ok(Command::Render(options))
+}
+
+pub fn is_scene_file(scene: &str) -> bool {
+    scene.ends_with(".toml") || is_gltf(scene)
+}
+
+pub fn is_gltf(scene: &str) -> bool {
+    scene.ends_with(".gltf") || scene.ends_with(".glb")
+}
+
+// Checked after the command line has been merged with the scene file, so
+// bad values are caught wherever they came from.
+pub fn validate(settings: &RenderSettings) -> Result<(), String> {
+    if settings.image_width < 2 || settings.image_height < 2 {
+        return Err(format!(
+            "image must be at least 2x2 pixels, got {}x{}",
+            settings.image_width, settings.image_height
+        ));
+    }
+    if settings.samples_per_pixel == 0 {
+        return Err(String::from("samples must be at least 1"));
+    }
+    if settings.max_depth < 1 {
+        return Err(String::from("depth must be at least 1"));
+    }
+    if settings.threads == 0 {
+        return Err(String::from("threads must be at least 1"));
+    }
+    if settings.tile_size == 0 {
+        return Err(String::from("tile size must be at least 1"));
+    }
+    if !settings.tone_mapping.exposure.is_finite() {
+        return Err(String::from("exposure must be a finite number of stops"));
+    }
+    if settings.tone_mapping.white.is_nan() || settings.tone_mapping.white <= 0. {
+        return Err(String::from("white point must be positive"));
+    }
+    Ok(())
+}
+
+fn parse_number<T>(flag: &str, value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|e| format!("invalid value {:?} for {}: {}", value, flag, e))
+}
+
+fn parse_aspect_ratio(value: &str) -> Result<f32, String> {
+    let ratio = match value.split_once(':') {
+        Some((w, h)) => {
+            let w: f32 = parse_number("--aspect-ratio", w)?;
+            let h: f32 = parse_number("--aspect-ratio", h)?;
+            w / h
+        }
+        None => parse_number("--aspect-ratio", value)?,
+    };
+
+    if !ratio.is_finite() || ratio <= 0. {
+        return Err(format!("invalid aspect ratio {:?}", value));
+    }
+    Ok(ratio)
+}
+
+fn parse_vec3(flag: &str, value: &str) -> Result<Vec3, String> {
+    let parts = value
+        .split(',')
+        .map(|p| parse_number::<f32>(flag, p.trim()))
+        .collect::<Result<Vec<f32>, String>>()?;
+
+    match parts[..] {
+        [x, y, z] if x.is_finite() && y.is_finite() && z.is_finite() => Ok(Vec3 { x, y, z }),
+        _ => Err(format!(
+            "{} expects three numbers x,y,z, got {:?}",
+            flag, value
+        )),
+    }
+}
+
+fn parse_shutter(value: &str) -> Result<(f32, f32), String> {
+    let (open, close) = value
+        .split_once(',')
+        .ok_or_else(|| format!("--shutter expects two times open,close, got {:?}", value))?;
+    let open: f32 = parse_number("--shutter", open.trim())?;
+    let close: f32 = parse_number("--shutter", close.trim())?;
+
+    if !open.is_finite() || !close.is_finite() || open > close {
+        return Err(format!(
+            "--shutter must open before it closes, got {:?}",
+            value
+        ));
+    }
+    Ok((open, close))
+}
@@ -0,0 +1,199 @@
+use crate::tonemap::ToneMapping;
+use crate::vec3::Color;
+use image::codecs::hdr::HdrEncoder;
+use image::{ImageBuffer, ImageError, RgbImage};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+// Linear radiance, one averaged `Color` per pixel with row 0 at the top.
+// Nothing is clamped until the image is converted to 8 bit.
+#[derive(Clone)]
+pub struct Framebuffer {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<Color>,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Image(ImageError),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "{}", e),
+            SaveError::Image(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<ImageError> for SaveError {
+    fn from(e: ImageError) -> Self {
+        SaveError::Image(e)
+    }
+}
+
+impl Framebuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::new(0.); (width * height) as usize],
+        }
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, pixel: Color) {
+        self.pixels[(y * self.width + x) as usize] = pixel;
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Color {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    pub fn to_image(&self, tone_mapping: &ToneMapping) -> RgbImage {
+        ImageBuffer::from_fn(self.width, self.height, |x, y| {
+            let p = tone_mapping.to_rgb(self.get(x, y));
+            image::Rgb([p.r, p.g, p.b])
+        })
+    }
+
+    // Picks the format from the extension: .exr, .hdr and .pfm keep the full
+    // float range and ignore the tone mapping, anything else goes through
+    // `to_image` and the image crate.
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+        tone_mapping: &ToneMapping,
+    ) -> Result<(), SaveError> {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("exr") => self.write_exr(&mut BufWriter::new(File::create(path)?))?,
+            Some("hdr") => self.write_hdr(&mut BufWriter::new(File::create(path)?))?,
+            Some("pfm") => self.write_pfm(&mut BufWriter::new(File::create(path)?))?,
+            _ => self.to_image(tone_mapping).save(path)?,
+        }
+        Ok(())
+    }
+
+    // Portable float map: a text header followed by little endian floats,
+    // scanlines stored bottom to top.
+    pub fn write_pfm<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "PF\n{} {}\n-1.0\n", self.width, self.height)?;
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                let p = self.get(x, y);
+                for c in &[p.x, p.y, p.z] {
+                    w.write_all(&c.to_le_bytes())?;
+                }
+            }
+        }
+        w.flush()
+    }
+
+    // Radiance RGBE, through the encoder that ships with the image crate.
+    pub fn write_hdr<W: Write>(&self, w: &mut W) -> Result<(), ImageError> {
+        let pixels: Vec<image::Rgb<f32>> = self
+            .pixels
+            .iter()
+            .map(|p| image::Rgb([p.x.max(0.), p.y.max(0.), p.z.max(0.)]))
+            .collect();
+        HdrEncoder::new(w).encode(&pixels, self.width as usize, self.height as usize)
+    }
+
+    // Single part scanline OpenEXR with uncompressed 32 bit float B, G, R
+    // channels, one scanline per block.
+    pub fn write_exr<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let width = self.width as i32;
+        let height = self.height as i32;
+
+        let mut header = vec![];
+        header.extend_from_slice(&[0x76, 0x2f, 0x31, 0x01]);
+        header.extend_from_slice(&2u32.to_le_bytes());
+
+        let mut channels = vec![];
+        for name in &["B", "G", "R"] {
+            channels.extend_from_slice(name.as_bytes());
+            channels.push(0);
+            channels.extend_from_slice(&2i32.to_le_bytes()); // FLOAT
+            channels.extend_from_slice(&[0, 0, 0, 0]); // pLinear + reserved
+            channels.extend_from_slice(&1i32.to_le_bytes()); // xSampling
+            channels.extend_from_slice(&1i32.to_le_bytes()); // ySampling
+        }
+        channels.push(0);
+        exr_attribute(&mut header, "channels", "chlist", &channels);
+        exr_attribute(&mut header, "compression", "compression", &[0]);
+
+        let mut window = vec![];
+        for v in &[0, 0, width - 1, height - 1] {
+            window.extend_from_slice(&v.to_le_bytes());
+        }
+        exr_attribute(&mut header, "dataWindow", "box2i", &window);
+        exr_attribute(&mut header, "displayWindow", "box2i", &window);
+        exr_attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+        exr_attribute(
+            &mut header,
+            "pixelAspectRatio",
+            "float",
+            &1f32.to_le_bytes(),
+        );
+        exr_attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+        exr_attribute(
+            &mut header,
+            "screenWindowWidth",
+            "float",
+            &1f32.to_le_bytes(),
+        );
+        header.push(0);
+        w.write_all(&header)?;
+
+        let line_size = 3 * 4 * self.width as u64;
+        let table_size = 8 * self.height as u64;
+        for y in 0..self.height as u64 {
+            let offset = header.len() as u64 + table_size + y * (8 + line_size);
+            w.write_all(&offset.to_le_bytes())?;
+        }
+
+        for y in 0..self.height {
+            w.write_all(&(y as i32).to_le_bytes())?;
+            w.write_all(&(line_size as i32).to_le_bytes())?;
+            for channel in 0..3 {
+                for x in 0..self.width {
+                    let p = self.get(x, y);
+                    let c = match channel {
+                        0 => p.z,
+                        1 => p.y,
+                        _ => p.x,
+                    };
+                    w.write_all(&c.to_le_bytes())?;
+                }
+            }
+        }
+        w.flush()
+    }
+}
+
+fn exr_attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(kind.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
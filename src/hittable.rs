@@ -1,14 +1,43 @@
+use crate::aabb::Aabb;
 use crate::ray::Ray;
-use crate::vec3::Point3;
+use crate::sampler::Sampler;
+use crate::vec3::{Color, Point3};
 use crate::vec3::{Math, Vec3};
+use std::sync::Arc;
 
 #[derive(Copy, Clone)]
 pub struct HitRecord {
     pub p: Point3,
     pub normal: Vec3,
     pub t: f32,
+    // surface coordinates in [0, 1], for textures
+    pub u: f32,
+    pub v: f32,
     pub front_face: bool,
     pub material_index: i32,
+    // interpolated vertex color, on meshes that have them
+    pub color: Option<Color>,
+}
+
+impl HitRecord {
+    pub fn new() -> Self {
+        Self {
+            p: Vec3::new(0.),
+            normal: Vec3::new(0.),
+            t: 0.,
+            u: 0.,
+            v: 0.,
+            front_face: false,
+            material_index: 0,
+            color: None,
+        }
+    }
+}
+
+impl Default for HitRecord {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub trait SetFaceNormal {
@@ -16,14 +45,24 @@ pub trait SetFaceNormal {
 }
 
 impl SetFaceNormal for HitRecord {
-    fn set_face_normal<'a>(&mut self, ray: &Ray, outward: Vec3) {
+    fn set_face_normal(&mut self, ray: &Ray, outward: Vec3) {
         self.front_face = ray.direction.dot(outward) < 0.;
         self.normal = if self.front_face { outward } else { -outward };
     }
 }
 
 pub trait Hittable: HitClone {
-    fn hit<'a>(&self, r: &Ray, t_min: f32, t_max: f32, hit_record: &mut HitRecord) -> bool;
+    // `sampler` belongs to the camera sample being traced, for objects whose
+    // hits are random, such as participating media. Surfaces ignore it.
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f32,
+        t_max: f32,
+        hit_record: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool;
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool;
 }
 
 pub trait HitClone {
@@ -44,3 +83,22 @@ impl Clone for Box<dyn Hittable> {
         self.clone_box()
     }
 }
+
+// Lets shared geometry be wrapped, e.g. instanced several times with
+// different transforms.
+impl Hittable for Arc<dyn Hittable + Sync + Send> {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f32,
+        t_max: f32,
+        hit_record: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        self.as_ref().hit(r, t_min, t_max, hit_record, sampler)
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        self.as_ref().bounding_box(time0, time1, output_box)
+    }
+}
@@ -0,0 +1,45 @@
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Vec3, Vec3Attributes};
+
+pub mod aabb;
+pub mod bvh;
+pub mod camera;
+pub mod framebuffer;
+pub mod hittable;
+pub mod loaders;
+pub mod mat4;
+pub mod materials;
+pub mod onb;
+pub mod primitives;
+pub mod quaternion;
+pub mod ray;
+pub mod renderer;
+pub mod roots;
+pub mod sampler;
+pub mod scene;
+pub mod scene_file;
+pub mod scenes;
+pub mod sdf;
+pub mod textures;
+pub mod tonemap;
+pub mod vec3;
+pub mod volumes;
+
+pub use crate::camera::Camera;
+pub use crate::framebuffer::Framebuffer;
+pub use crate::renderer::{render, render_with_progress, RenderSettings};
+pub use crate::scene::Scene;
+
+pub fn random_in_unit_sphere(sampler: &mut Sampler) -> Vec3 {
+    loop {
+        let p = Vec3::random_rng(sampler, -1., 1.);
+        if p.length_squared() >= 1. {
+            continue;
+        }
+        return p;
+    }
+}
+
+pub fn random_unit_vector(sampler: &mut Sampler) -> Vec3 {
+    random_in_unit_sphere(sampler).unit()
+}
@@ -0,0 +1,772 @@
+use crate::aabb::Aabb;
+use crate::camera::Camera;
+use crate::hittable::Hittable;
+use crate::loaders::LoadError;
+use crate::mat4::Mat4;
+use crate::materials::dielectric::Dialectric;
+use crate::materials::diffuse_light::DiffuseLight;
+use crate::materials::lambertian::Lambertian;
+use crate::materials::material::Material;
+use crate::materials::metal::Metal;
+use crate::primitives::triangle_mesh::{MeshData, TriangleMesh};
+use crate::scene::{Scene, WorldItem};
+use crate::textures::image_texture::ImageTexture;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vec3::{Color, Math, Point3, Vec3, Vec3Attributes};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+// A glTF 2.0 scene, .gltf with external or embedded buffers or binary .glb.
+// Node transforms are baked into the vertices, every mesh primitive becomes
+// one world item. `camera` is the first perspective camera in the scene.
+pub struct Gltf {
+    pub world: Vec<WorldItem>,
+    pub camera: Option<Camera>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Document {
+    scene: Option<usize>,
+    scenes: Vec<SceneDesc>,
+    nodes: Vec<Node>,
+    meshes: Vec<MeshDesc>,
+    materials: Vec<MaterialDesc>,
+    textures: Vec<TextureDesc>,
+    images: Vec<ImageDesc>,
+    cameras: Vec<CameraDesc>,
+    accessors: Vec<Accessor>,
+    buffer_views: Vec<BufferView>,
+    buffers: Vec<BufferDesc>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct SceneDesc {
+    nodes: Vec<usize>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Node {
+    children: Vec<usize>,
+    mesh: Option<usize>,
+    camera: Option<usize>,
+    matrix: Option<[f32; 16]>,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 4]>,
+    scale: Option<[f32; 3]>,
+}
+
+#[derive(Deserialize)]
+struct MeshDesc {
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Deserialize)]
+struct Primitive {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    #[serde(default = "default_mode")]
+    mode: u32,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct MaterialDesc {
+    pbr_metallic_roughness: Option<Pbr>,
+    emissive_factor: Option<[f32; 3]>,
+    extensions: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Pbr {
+    #[serde(default = "default_base_color")]
+    base_color_factor: [f32; 4],
+    base_color_texture: Option<TextureInfo>,
+    #[serde(default = "default_factor")]
+    metallic_factor: f32,
+    #[serde(default = "default_factor")]
+    roughness_factor: f32,
+}
+
+#[derive(Deserialize)]
+struct TextureInfo {
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct TextureDesc {
+    source: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImageDesc {
+    uri: Option<String>,
+    buffer_view: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct CameraDesc {
+    perspective: Option<Perspective>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Perspective {
+    yfov: f32,
+    aspect_ratio: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    #[serde(default)]
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+    sparse: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferDesc {
+    uri: Option<String>,
+    byte_length: usize,
+}
+
+fn default_mode() -> u32 {
+    4
+}
+
+fn default_base_color() -> [f32; 4] {
+    [1., 1., 1., 1.]
+}
+
+fn default_factor() -> f32 {
+    1.
+}
+
+pub fn load<P: AsRef<Path>>(path: P, aspect_ratio: f32) -> Result<Gltf, LoadError> {
+    let path = path.as_ref();
+    let file = path.display().to_string();
+    let bytes = fs::read(path)
+        .map_err(|e| LoadError::new(&file, None, format!("could not read file: {}", e)))?;
+    parse(
+        &file,
+        &bytes,
+        path.parent().unwrap_or_else(|| Path::new("")),
+        aspect_ratio,
+    )
+}
+
+// A scene straight from a glTF file: its camera, or one framing the whole
+// model from the front when the file has none.
+pub fn load_scene<P: AsRef<Path>>(path: P, aspect_ratio: f32) -> Result<Scene, LoadError> {
+    let gltf = load(&path, aspect_ratio)?;
+    let camera = match gltf.camera {
+        Some(camera) => camera,
+        None => {
+            let mut bounds = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+            if !gltf.world.bounding_box(0., 1., &mut bounds) {
+                return Err(LoadError::new(
+                    &path.as_ref().display().to_string(),
+                    None,
+                    String::from("no camera and nothing to frame"),
+                ));
+            }
+            let center = bounds.centroid();
+            let radius = 0.5 * (bounds.maximum - bounds.minimum).length();
+            let vfov: f32 = 40.;
+            let distance = radius / (vfov.to_radians() / 2.).sin();
+            let lookfrom = center
+                + Vec3 {
+                    x: 0.,
+                    y: 0.,
+                    z: distance,
+                };
+            Camera::new(
+                lookfrom,
+                center,
+                Vec3 {
+                    x: 0.,
+                    y: 1.,
+                    z: 0.,
+                },
+                vfov,
+                aspect_ratio,
+                0.,
+                distance,
+            )
+        }
+    };
+
+    let mut scene = Scene::new(camera);
+    scene.world = gltf.world;
+    Ok(scene)
+}
+
+pub fn parse(file: &str, bytes: &[u8], base: &Path, aspect_ratio: f32) -> Result<Gltf, LoadError> {
+    let error = |message: String| LoadError::new(file, None, message);
+
+    let (json, binary) = if bytes.starts_with(b"glTF") {
+        split_glb(bytes).map_err(error)?
+    } else {
+        (bytes, None)
+    };
+    let doc: Document = serde_json::from_slice(json)
+        .map_err(|e| LoadError::new(file, Some(e.line()), format!("invalid glTF: {}", e)))?;
+
+    let mut buffers = vec![];
+    for (i, buffer) in doc.buffers.iter().enumerate() {
+        let data = match (&buffer.uri, i, binary) {
+            (Some(uri), _, _) => read_uri(uri, base).map_err(error)?,
+            (None, 0, Some(binary)) => binary.to_vec(),
+            (None, _, _) => return Err(error(format!("buffer {} has no data", i))),
+        };
+        if data.len() < buffer.byte_length {
+            return Err(error(format!(
+                "buffer {} holds {} bytes, {} expected",
+                i,
+                data.len(),
+                buffer.byte_length
+            )));
+        }
+        buffers.push(data);
+    }
+
+    let loader = Loader {
+        doc: &doc,
+        buffers,
+        base,
+    };
+
+    let materials = doc
+        .materials
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            loader
+                .material(m)
+                .map_err(|e| error(format!("material {}: {}", i, e)))
+        })
+        .collect::<Result<Vec<_>, LoadError>>()?;
+    let default: Arc<dyn Material + Sync + Send> = Arc::new(Lambertian::new(Color::new(0.5)));
+
+    let roots = match doc
+        .scene
+        .or(if doc.scenes.is_empty() { None } else { Some(0) })
+    {
+        Some(scene) => doc
+            .scenes
+            .get(scene)
+            .ok_or_else(|| error(format!("scene {} does not exist", scene)))?
+            .nodes
+            .clone(),
+        // no scenes at all, every node without a parent is a root
+        None => (0..doc.nodes.len())
+            .filter(|i| !doc.nodes.iter().any(|n| n.children.contains(i)))
+            .collect(),
+    };
+
+    let mut gltf = Gltf {
+        world: vec![],
+        camera: None,
+    };
+    let mut stack: Vec<(usize, Mat4, usize)> =
+        roots.iter().map(|&n| (n, Mat4::identity(), 0)).collect();
+    stack.reverse();
+    while let Some((index, parent, depth)) = stack.pop() {
+        let node = doc
+            .nodes
+            .get(index)
+            .ok_or_else(|| error(format!("node {} does not exist", index)))?;
+        if depth > doc.nodes.len() {
+            return Err(error(String::from("the node hierarchy has a cycle")));
+        }
+        let transform = parent * node_matrix(node);
+
+        if let Some(mesh) = node.mesh {
+            let mesh = doc
+                .meshes
+                .get(mesh)
+                .ok_or_else(|| error(format!("mesh {} does not exist", mesh)))?;
+            for primitive in &mesh.primitives {
+                let data = loader
+                    .primitive(primitive, &transform)
+                    .map_err(|e| error(format!("node {}: {}", index, e)))?;
+                let data = match data {
+                    Some(data) => data,
+                    None => continue,
+                };
+                let material = match primitive.material {
+                    Some(m) => materials
+                        .get(m)
+                        .cloned()
+                        .ok_or_else(|| error(format!("material {} does not exist", m)))?,
+                    None => default.clone(),
+                };
+                let mesh =
+                    TriangleMesh::new(data).map_err(|e| error(format!("node {}: {}", index, e)))?;
+                gltf.world
+                    .push((Arc::new(mesh) as Arc<dyn Hittable + Sync + Send>, material));
+            }
+        }
+
+        if let (Some(camera), None) = (node.camera, &gltf.camera) {
+            let desc = doc
+                .cameras
+                .get(camera)
+                .ok_or_else(|| error(format!("camera {} does not exist", camera)))?;
+            // orthographic cameras have no equivalent and are skipped
+            if let Some(perspective) = &desc.perspective {
+                let lookfrom = transform.transform_point(Vec3::new(0.));
+                let forward = transform.transform_vector(Vec3 {
+                    x: 0.,
+                    y: 0.,
+                    z: -1.,
+                });
+                let vup = transform.transform_vector(Vec3 {
+                    x: 0.,
+                    y: 1.,
+                    z: 0.,
+                });
+                gltf.camera = Some(Camera::new(
+                    lookfrom,
+                    lookfrom + forward.unit(),
+                    vup,
+                    perspective.yfov.to_degrees(),
+                    perspective.aspect_ratio.unwrap_or(aspect_ratio),
+                    0.,
+                    1.,
+                ));
+            }
+        }
+
+        for &child in node.children.iter().rev() {
+            stack.push((child, transform, depth + 1));
+        }
+    }
+
+    Ok(gltf)
+}
+
+struct Loader<'a> {
+    doc: &'a Document,
+    buffers: Vec<Vec<u8>>,
+    base: &'a Path,
+}
+
+impl Loader<'_> {
+    // glTF metallic-roughness onto the closest material we have:
+    //   transmission                 -> Dialectric with the KHR_materials_ior ior
+    //   an emissive color            -> DiffuseLight
+    //   metallic at least one half   -> Metal, fuzz from the roughness
+    //   anything else                -> Lambertian with the base color
+    fn material(&self, desc: &MaterialDesc) -> Result<Arc<dyn Material + Sync + Send>, String> {
+        let extension = |name: &str, field: &str| {
+            desc.extensions
+                .get(name)
+                .and_then(|e| e.get(field))
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+        };
+
+        if extension("KHR_materials_transmission", "transmissionFactor").unwrap_or(0.) > 0.5 {
+            let ir = extension("KHR_materials_ior", "ior").unwrap_or(1.5);
+            return Ok(Arc::new(Dialectric { ir }));
+        }
+
+        let emissive = desc.emissive_factor.unwrap_or([0., 0., 0.]);
+        if emissive.iter().any(|c| *c > 0.) {
+            let strength =
+                extension("KHR_materials_emissive_strength", "emissiveStrength").unwrap_or(1.);
+            return Ok(Arc::new(DiffuseLight::new(
+                Vec3::new(strength) * color(&emissive),
+            )));
+        }
+
+        let (factor, texture, metallic, roughness) = match &desc.pbr_metallic_roughness {
+            Some(pbr) => (
+                pbr.base_color_factor,
+                pbr.base_color_texture.as_ref().map(|t| t.index),
+                pbr.metallic_factor,
+                pbr.roughness_factor,
+            ),
+            None => (default_base_color(), None, 1., 1.),
+        };
+        let factor = color(&[factor[0], factor[1], factor[2]]);
+
+        let albedo: Arc<dyn Texture + Sync + Send> = match texture {
+            Some(index) => Arc::new(TintedTexture {
+                texture: self.texture(index)?,
+                tint: factor,
+            }),
+            None => Arc::new(SolidColor::new(factor)),
+        };
+
+        if metallic >= 0.5 {
+            return Ok(Arc::new(Metal::textured(albedo, roughness.clamp(0., 1.))));
+        }
+        Ok(Arc::new(Lambertian::textured(albedo)))
+    }
+
+    fn texture(&self, index: usize) -> Result<ImageTexture, String> {
+        let source = self
+            .doc
+            .textures
+            .get(index)
+            .and_then(|t| t.source)
+            .ok_or_else(|| format!("texture {} has no image", index))?;
+        let image = self
+            .doc
+            .images
+            .get(source)
+            .ok_or_else(|| format!("image {} does not exist", source))?;
+
+        let bytes = match (&image.uri, image.buffer_view) {
+            (Some(uri), _) => read_uri(uri, self.base)?,
+            (None, Some(view)) => self.view(view)?.to_vec(),
+            (None, None) => return Err(format!("image {} has no data", source)),
+        };
+        ImageTexture::from_memory(&bytes).map_err(|e| format!("image {}: {}", source, e))
+    }
+
+    fn view(&self, index: usize) -> Result<&[u8], String> {
+        let view = self
+            .doc
+            .buffer_views
+            .get(index)
+            .ok_or_else(|| format!("buffer view {} does not exist", index))?;
+        self.buffers
+            .get(view.buffer)
+            .and_then(|b| b.get(view.byte_offset..view.byte_offset + view.byte_length))
+            .ok_or_else(|| format!("buffer view {} is out of range", index))
+    }
+
+    // All values of an accessor, `components` per element. Normalized
+    // integers are mapped to [0, 1] or [-1, 1].
+    fn accessor(&self, index: usize) -> Result<(Vec<f64>, usize), String> {
+        let accessor = self
+            .doc
+            .accessors
+            .get(index)
+            .ok_or_else(|| format!("accessor {} does not exist", index))?;
+        if accessor.sparse.is_some() {
+            return Err(format!(
+                "accessor {} is sparse, which is not supported",
+                index
+            ));
+        }
+        let components = match accessor.kind.as_str() {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            kind => return Err(format!("accessor {} has unsupported type {}", index, kind)),
+        };
+        let size = match accessor.component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            other => {
+                return Err(format!(
+                    "accessor {} has unknown component type {}",
+                    index, other
+                ))
+            }
+        };
+
+        let view_index = match accessor.buffer_view {
+            Some(view) => view,
+            // no view means all zeros
+            None => return Ok((vec![0.; accessor.count * components], components)),
+        };
+        let data = self.view(view_index)?;
+        let stride = self.doc.buffer_views[view_index]
+            .byte_stride
+            .unwrap_or(size * components);
+
+        let mut values = Vec::with_capacity(accessor.count * components);
+        for element in 0..accessor.count {
+            for component in 0..components {
+                let start = accessor.byte_offset + element * stride + component * size;
+                let raw = data
+                    .get(start..start + size)
+                    .ok_or_else(|| format!("accessor {} reads past its buffer view", index))?;
+                let value = match accessor.component_type {
+                    5120 => raw[0] as i8 as f64,
+                    5121 => raw[0] as f64,
+                    5122 => i16::from_le_bytes([raw[0], raw[1]]) as f64,
+                    5123 => u16::from_le_bytes([raw[0], raw[1]]) as f64,
+                    5125 => u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+                    _ => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+                };
+                values.push(if accessor.normalized {
+                    match accessor.component_type {
+                        5120 => (value / 127.).max(-1.),
+                        5121 => value / 255.,
+                        5122 => (value / 32767.).max(-1.),
+                        5123 => value / 65535.,
+                        _ => value,
+                    }
+                } else {
+                    value
+                });
+            }
+        }
+        Ok((values, components))
+    }
+
+    fn attribute(
+        &self,
+        primitive: &Primitive,
+        name: &str,
+        components: usize,
+    ) -> Result<Option<Vec<f64>>, String> {
+        let index = match primitive.attributes.get(name) {
+            Some(index) => *index,
+            None => return Ok(None),
+        };
+        let (values, found) = self.accessor(index)?;
+        if found != components {
+            return Err(format!(
+                "{} has {} components, {} expected",
+                name, found, components
+            ));
+        }
+        Ok(Some(values))
+    }
+
+    fn primitive(
+        &self,
+        primitive: &Primitive,
+        transform: &Mat4,
+    ) -> Result<Option<MeshData>, String> {
+        // points and lines have no surface
+        if primitive.mode < 4 {
+            return Ok(None);
+        }
+        if primitive.mode > 6 {
+            return Err(format!("unknown primitive mode {}", primitive.mode));
+        }
+
+        let positions = self
+            .attribute(primitive, "POSITION", 3)?
+            .ok_or_else(|| String::from("primitive has no POSITION"))?;
+        let count = positions.len() / 3;
+        // singular transforms flatten the mesh, its normals no longer matter
+        let normal_matrix = transform.inverse().unwrap_or_default().transpose();
+
+        let positions = positions
+            .chunks(3)
+            .map(|p| transform.transform_point(vec3(p)))
+            .collect();
+        let normals = self.attribute(primitive, "NORMAL", 3)?.map(|n| {
+            n.chunks(3)
+                .map(|n| normal_matrix.transform_vector(vec3(n)))
+                .collect()
+        });
+        // glTF has v pointing down the image
+        let uvs = self.attribute(primitive, "TEXCOORD_0", 2)?.map(|uv| {
+            uv.chunks(2)
+                .map(|uv| [uv[0] as f32, 1. - uv[1] as f32])
+                .collect()
+        });
+
+        let order: Vec<usize> = match primitive.indices {
+            Some(index) => self
+                .accessor(index)?
+                .0
+                .iter()
+                .map(|&i| i as usize)
+                .collect(),
+            None => (0..count).collect(),
+        };
+        let indices = match primitive.mode {
+            4 => order.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect(),
+            // strips alternate winding to keep every triangle facing the same way
+            5 => (2..order.len())
+                .map(|i| {
+                    if i % 2 == 0 {
+                        [order[i - 2], order[i - 1], order[i]]
+                    } else {
+                        [order[i - 1], order[i - 2], order[i]]
+                    }
+                })
+                .collect(),
+            _ => (2..order.len())
+                .map(|i| [order[0], order[i - 1], order[i]])
+                .collect(),
+        };
+
+        Ok(Some(MeshData {
+            positions,
+            indices,
+            normals,
+            uvs,
+            colors: None,
+        }))
+    }
+}
+
+// The base color texture scaled by the base color factor.
+#[derive(Clone)]
+struct TintedTexture {
+    texture: ImageTexture,
+    tint: Color,
+}
+
+impl Texture for TintedTexture {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color {
+        self.tint * self.texture.value(u, v, p)
+    }
+}
+
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), String> {
+    let word = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or_else(|| String::from("truncated glb file"))
+    };
+    if word(4)? != 2 {
+        return Err(format!("unsupported glb version {}", word(4)?));
+    }
+
+    let mut json = None;
+    let mut binary = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let length = word(offset)?;
+        let kind = word(offset + 4)?;
+        let data = bytes
+            .get(offset + 8..offset + 8 + length)
+            .ok_or_else(|| String::from("truncated glb chunk"))?;
+        match kind {
+            0x4e4f_534a => json = json.or(Some(data)),
+            0x004e_4942 => binary = binary.or(Some(data)),
+            _ => {}
+        }
+        offset += 8 + length;
+    }
+
+    json.map(|json| (json, binary))
+        .ok_or_else(|| String::from("glb file has no JSON chunk"))
+}
+
+fn read_uri(uri: &str, base: &Path) -> Result<Vec<u8>, String> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let (_, encoded) = data
+            .split_once(";base64,")
+            .ok_or_else(|| String::from("only base64 data uris are supported"))?;
+        return decode_base64(encoded);
+    }
+    let path = base.join(percent_decode(uri));
+    fs::read(&path).map_err(|e| format!("could not read {}: {}", path.display(), e))
+}
+
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn decode_base64(encoded: &str) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut count = 0;
+    for c in encoded.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            _ => return Err(format!("invalid base64 character {:?}", c as char)),
+        };
+        bits = (bits << 6) | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+        }
+    }
+    Ok(out)
+}
+
+fn node_matrix(node: &Node) -> Mat4 {
+    if let Some(m) = &node.matrix {
+        return Mat4::from_cols(m);
+    }
+
+    let [tx, ty, tz] = node.translation.unwrap_or([0., 0., 0.]);
+    let [sx, sy, sz] = node.scale.unwrap_or([1., 1., 1.]);
+    Mat4::translation(Vec3 {
+        x: tx,
+        y: ty,
+        z: tz,
+    }) * Mat4::from_quaternion(node.rotation.unwrap_or([0., 0., 0., 1.]))
+        * Mat4::scaling(Vec3 {
+            x: sx,
+            y: sy,
+            z: sz,
+        })
+}
+
+fn vec3(v: &[f64]) -> Vec3 {
+    Vec3 {
+        x: v[0] as f32,
+        y: v[1] as f32,
+        z: v[2] as f32,
+    }
+}
+
+fn color(c: &[f32; 3]) -> Color {
+    Color {
+        x: c[0],
+        y: c[1],
+        z: c[2],
+    }
+}
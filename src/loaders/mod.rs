@@ -0,0 +1,35 @@
+use std::fmt;
+
+pub mod gltf;
+pub mod mtl;
+pub mod obj;
+pub mod ply;
+
+// Where a model file went wrong, with the line when there is one.
+#[derive(Debug)]
+pub struct LoadError {
+    pub file: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl LoadError {
+    pub fn new(file: &str, line: Option<usize>, message: String) -> Self {
+        Self {
+            file: file.to_string(),
+            line,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file, line, self.message),
+            None => write!(f, "{}: {}", self.file, self.message),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
@@ -0,0 +1,253 @@
+use crate::loaders::LoadError;
+use crate::materials::dielectric::Dialectric;
+use crate::materials::lambertian::Lambertian;
+use crate::materials::material::Material;
+use crate::materials::metal::Metal;
+use crate::textures::image_texture::ImageTexture;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vec3::Color;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+pub type Materials = BTreeMap<String, Arc<dyn Material + Sync + Send>>;
+
+// The subset of a Wavefront material that maps onto our materials.
+struct MtlDesc {
+    name: String,
+    line: usize,
+    kd: Color,
+    ks: Color,
+    ns: f32,
+    ni: Option<f32>,
+    d: f32,
+    illum: Option<u32>,
+    map_kd: Option<String>,
+}
+
+impl MtlDesc {
+    fn new(name: String, line: usize) -> Self {
+        Self {
+            name,
+            line,
+            kd: Color::new(0.8),
+            ks: Color::new(0.),
+            ns: 0.,
+            ni: None,
+            d: 1.,
+            illum: None,
+            map_kd: None,
+        }
+    }
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Materials, LoadError> {
+    let path = path.as_ref();
+    let file = path.display().to_string();
+    let source = fs::read_to_string(path)
+        .map_err(|e| LoadError::new(&file, None, format!("could not read file: {}", e)))?;
+    parse(
+        &file,
+        &source,
+        path.parent().unwrap_or_else(|| Path::new("")),
+    )
+}
+
+// Texture paths are resolved against `base`. Materials are chosen as:
+//   d < 1, Tr > 0 or a refracting illum model -> Dialectric with ir = Ni
+//   Ks brighter than Kd                       -> Metal, fuzz from Ns
+//   anything else                             -> Lambertian with Kd or map_Kd
+pub fn parse(file: &str, source: &str, base: &Path) -> Result<Materials, LoadError> {
+    let mut descs: Vec<MtlDesc> = vec![];
+
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| LoadError::new(file, Some(number), message);
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut parts = line.split_whitespace();
+        let keyword = match parts.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let args: Vec<&str> = parts.collect();
+
+        if keyword == "newmtl" {
+            if args.is_empty() {
+                return Err(error(String::from("newmtl needs a name")));
+            }
+            descs.push(MtlDesc::new(args.join(" "), number));
+            continue;
+        }
+
+        let desc = match descs.last_mut() {
+            Some(desc) => desc,
+            None => {
+                return Err(error(format!("{} before the first newmtl", keyword)));
+            }
+        };
+        match keyword {
+            "Kd" => desc.kd = color(&args).map_err(error)?,
+            "Ks" => desc.ks = color(&args).map_err(error)?,
+            "Ns" => desc.ns = number_arg(keyword, &args).map_err(error)?,
+            "Ni" => desc.ni = Some(number_arg(keyword, &args).map_err(error)?),
+            "d" => desc.d = number_arg(keyword, &args).map_err(error)?,
+            "Tr" => desc.d = 1. - number_arg::<f32>(keyword, &args).map_err(error)?,
+            "illum" => desc.illum = Some(number_arg(keyword, &args).map_err(error)?),
+            "map_Kd" => match texture_path(&line[keyword.len()..]) {
+                Some(path) => desc.map_kd = Some(path.to_string()),
+                None => return Err(error(String::from("map_Kd needs a file name"))),
+            },
+            // Ka, Ke, bump maps and the rest have nothing to map onto
+            _ => {}
+        }
+    }
+
+    let mut materials = Materials::new();
+    for desc in descs {
+        let material =
+            build(&desc, base).map_err(|message| LoadError::new(file, Some(desc.line), message))?;
+        materials.insert(desc.name, material);
+    }
+    Ok(materials)
+}
+
+fn build(desc: &MtlDesc, base: &Path) -> Result<Arc<dyn Material + Sync + Send>, String> {
+    let refracts = matches!(desc.illum, Some(4) | Some(6) | Some(7) | Some(9));
+    if desc.d < 1. || refracts {
+        let ir = desc.ni.filter(|ir| *ir > 0. && *ir != 1.).unwrap_or(1.5);
+        return Ok(Arc::new(Dialectric { ir }));
+    }
+
+    if max(desc.ks) > max(desc.kd) {
+        // Phong exponent to a rough equivalent of the fuzz radius
+        let fuzz = (2. / (desc.ns.max(0.) + 2.)).sqrt().min(1.);
+        return Ok(Arc::new(Metal::new(desc.ks, fuzz)));
+    }
+
+    let albedo: Arc<dyn Texture + Sync + Send> = match &desc.map_kd {
+        Some(path) => {
+            let path = base.join(path);
+            let texture = ImageTexture::load(&path)
+                .map_err(|e| format!("could not load texture {}: {}", path.display(), e))?;
+            Arc::new(texture)
+        }
+        None => Arc::new(SolidColor::new(desc.kd)),
+    };
+    Ok(Arc::new(Lambertian::textured(albedo)))
+}
+
+// The file name of a texture statement, which may contain spaces, after
+// options such as `-s 2 2 1` or `-clamp on`.
+fn texture_path(mut rest: &str) -> Option<&str> {
+    loop {
+        rest = rest.trim_start();
+        let option = rest.split_whitespace().next()?;
+        // how many values the option takes, at least and at most
+        let (min, max) = match option {
+            "-blendu" | "-blendv" | "-boost" | "-cc" | "-clamp" | "-texres" | "-bm"
+            | "-imfchan" | "-type" => (1, 1),
+            "-mm" => (2, 2),
+            "-o" | "-s" | "-t" => (1, 3),
+            _ => break,
+        };
+        rest = &rest[option.len()..];
+        for i in 0..max {
+            let trimmed = rest.trim_start();
+            let value = trimmed.split_whitespace().next()?;
+            if i >= min && value.parse::<f32>().is_err() {
+                break;
+            }
+            rest = &trimmed[value.len()..];
+        }
+    }
+    let path = rest.trim_end();
+    if path.is_empty() {
+        None
+    } else {
+        Some(path)
+    }
+}
+
+fn max(c: Color) -> f32 {
+    c.x.max(c.y).max(c.z)
+}
+
+fn color(args: &[&str]) -> Result<Color, String> {
+    // a single value is a grey
+    let values = args
+        .iter()
+        .map(|a| parse_f32(a))
+        .collect::<Result<Vec<f32>, String>>()?;
+    match values[..] {
+        [v] => Ok(Color::new(v)),
+        [x, y, z] => Ok(Color { x, y, z }),
+        _ => Err(format!(
+            "expected a color as one or three numbers, got {:?}",
+            args.join(" ")
+        )),
+    }
+}
+
+fn number_arg<T: std::str::FromStr>(keyword: &str, args: &[&str]) -> Result<T, String> {
+    match args {
+        [value] => value
+            .parse()
+            .map_err(|_| format!("invalid number {:?} for {}", value, keyword)),
+        _ => Err(format!("{} expects one number", keyword)),
+    }
+}
+
+fn parse_f32(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(v) if v.is_finite() => Ok(v),
+        _ => Err(format!("invalid number {:?}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texture_paths_keep_spaces_and_skip_options() {
+        assert_eq!(texture_path(" wood.png"), Some("wood.png"));
+        assert_eq!(texture_path(" my texture.png "), Some("my texture.png"));
+        assert_eq!(
+            texture_path(" -s 2 2 1 -clamp on tex/my texture.png"),
+            Some("tex/my texture.png")
+        );
+        assert_eq!(texture_path(" -o 0.5 -mm 0 1 a.png"), Some("a.png"));
+        // -s takes up to three numbers, the path is whatever follows them
+        assert_eq!(texture_path(" -s 2 1.png"), Some("1.png"));
+        assert_eq!(texture_path(" -s 2 2 2 2.png"), Some("2.png"));
+        assert_eq!(texture_path(" -clamp on"), None);
+        assert_eq!(texture_path(""), None);
+    }
+
+    #[test]
+    fn reports_errors_with_their_line() {
+        let err = |source| match parse("test.mtl", source, Path::new("")) {
+            Ok(_) => panic!("{:?} parsed", source),
+            Err(e) => (e.line, e.message),
+        };
+        assert_eq!(
+            err("Kd 1 0 0\n"),
+            (Some(1), String::from("Kd before the first newmtl"))
+        );
+        assert_eq!(err("newmtl a\nKd 1 0\n").0, Some(2));
+        assert_eq!(err("newmtl a\n\nmap_Kd -clamp on\n").0, Some(3));
+    }
+
+    #[test]
+    fn names_may_contain_spaces() {
+        let materials = parse(
+            "test.mtl",
+            "newmtl red paint\nKd 1 0 0\nnewmtl b\n",
+            Path::new(""),
+        );
+        let names: Vec<String> = materials.unwrap().into_keys().collect();
+        assert_eq!(names, vec!["b", "red paint"]);
+    }
+}
@@ -0,0 +1,387 @@
+use crate::hittable::Hittable;
+use crate::loaders::mtl::{self, Materials};
+use crate::loaders::LoadError;
+use crate::materials::material::Material;
+use crate::primitives::triangle_mesh::{MeshData, TriangleMesh};
+use crate::scene::WorldItem;
+use crate::vec3::{Point3, Vec3};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+// One mesh per object, group and material combination, in the order they
+// first appear in the file.
+pub struct ObjMesh {
+    pub object: String,
+    pub group: String,
+    pub material: Option<String>,
+    pub data: MeshData,
+}
+
+pub struct Obj {
+    pub meshes: Vec<ObjMesh>,
+    pub materials: Materials,
+}
+
+// Indices into the file's position, uv and normal lists.
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+struct MeshBuilder {
+    object: String,
+    group: String,
+    material: Option<String>,
+    vertices: Vec<VertexKey>,
+    lookup: HashMap<VertexKey, usize>,
+    indices: Vec<[usize; 3]>,
+}
+
+impl MeshBuilder {
+    fn vertex(&mut self, key: VertexKey) -> usize {
+        let vertices = &mut self.vertices;
+        *self.lookup.entry(key).or_insert_with(|| {
+            vertices.push(key);
+            vertices.len() - 1
+        })
+    }
+
+    // Normals and uvs are only kept when every vertex of the mesh has one.
+    fn build(self, positions: &[Point3], uvs: &[[f32; 2]], normals: &[Vec3]) -> ObjMesh {
+        let has_uvs = self.vertices.iter().all(|v| v.1.is_some());
+        let has_normals = self.vertices.iter().all(|v| v.2.is_some());
+
+        ObjMesh {
+            data: MeshData {
+                positions: self.vertices.iter().map(|v| positions[v.0]).collect(),
+                indices: self.indices,
+                normals: if has_normals {
+                    Some(
+                        self.vertices
+                            .iter()
+                            .map(|v| normals[v.2.unwrap()])
+                            .collect(),
+                    )
+                } else {
+                    None
+                },
+                uvs: if has_uvs {
+                    Some(self.vertices.iter().map(|v| uvs[v.1.unwrap()]).collect())
+                } else {
+                    None
+                },
+                colors: None,
+            },
+            object: self.object,
+            group: self.group,
+            material: self.material,
+        }
+    }
+}
+
+impl Obj {
+    // Material names faces use that the mtllibs don't define, in the order
+    // they first appear. `into_world` gives those faces the default.
+    pub fn unknown_materials(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for name in self.meshes.iter().filter_map(|m| m.material.as_deref()) {
+            if !self.materials.contains_key(name) && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    // One world item per mesh. Faces that never had a `usemtl`, or one naming
+    // a material the mtllib lacks, get `default`.
+    pub fn into_world(self, default: Arc<dyn Material + Sync + Send>) -> Vec<WorldItem> {
+        let materials = self.materials;
+        self.meshes
+            .into_iter()
+            .map(|mesh| {
+                let material = mesh
+                    .material
+                    .as_ref()
+                    .and_then(|name| materials.get(name))
+                    .cloned()
+                    .unwrap_or_else(|| default.clone());
+                let mesh = TriangleMesh::new(mesh.data).expect("obj meshes are indexed on load");
+                (Arc::new(mesh) as Arc<dyn Hittable + Sync + Send>, material)
+            })
+            .collect()
+    }
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Obj, LoadError> {
+    let path = path.as_ref();
+    let file = path.display().to_string();
+    let source = fs::read_to_string(path)
+        .map_err(|e| LoadError::new(&file, None, format!("could not read file: {}", e)))?;
+    parse(
+        &file,
+        &source,
+        path.parent().unwrap_or_else(|| Path::new("")),
+    )
+}
+
+// `mtllib` files are resolved against `base`. Polygons are triangulated as
+// fans, which is exact for the convex faces exporters write.
+pub fn parse(file: &str, source: &str, base: &Path) -> Result<Obj, LoadError> {
+    let mut positions: Vec<Point3> = vec![];
+    let mut uvs: Vec<[f32; 2]> = vec![];
+    let mut normals: Vec<Vec3> = vec![];
+    let mut materials = Materials::new();
+
+    let mut object = String::new();
+    let mut group = String::from("default");
+    let mut material: Option<String> = None;
+    let mut builders: Vec<MeshBuilder> = vec![];
+
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| LoadError::new(file, Some(number), message);
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut parts = line.split_whitespace();
+        let keyword = match parts.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let args: Vec<&str> = parts.collect();
+
+        match keyword {
+            "v" => {
+                // an optional w is ignored
+                let v = floats(keyword, &args, 3, 4).map_err(error)?;
+                positions.push(Point3 {
+                    x: v[0],
+                    y: v[1],
+                    z: v[2],
+                });
+            }
+            "vt" => {
+                let v = floats(keyword, &args, 1, 3).map_err(error)?;
+                uvs.push([v[0], v.get(1).copied().unwrap_or(0.)]);
+            }
+            "vn" => {
+                let v = floats(keyword, &args, 3, 3).map_err(error)?;
+                normals.push(Vec3 {
+                    x: v[0],
+                    y: v[1],
+                    z: v[2],
+                });
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(error(format!(
+                        "a face needs at least 3 vertices, got {}",
+                        args.len()
+                    )));
+                }
+                let keys = args
+                    .iter()
+                    .map(|a| face_vertex(a, positions.len(), uvs.len(), normals.len()))
+                    .collect::<Result<Vec<VertexKey>, String>>()
+                    .map_err(error)?;
+
+                let builder = match builders
+                    .iter()
+                    .position(|b| b.object == object && b.group == group && b.material == material)
+                {
+                    Some(i) => &mut builders[i],
+                    None => {
+                        builders.push(MeshBuilder {
+                            object: object.clone(),
+                            group: group.clone(),
+                            material: material.clone(),
+                            vertices: vec![],
+                            lookup: HashMap::new(),
+                            indices: vec![],
+                        });
+                        builders.last_mut().unwrap()
+                    }
+                };
+
+                let first = builder.vertex(keys[0]);
+                for pair in keys[1..].windows(2) {
+                    let b = builder.vertex(pair[0]);
+                    let c = builder.vertex(pair[1]);
+                    builder.indices.push([first, b, c]);
+                }
+            }
+            "o" => object = args.join(" "),
+            "g" => {
+                group = if args.is_empty() {
+                    String::from("default")
+                } else {
+                    args.join(" ")
+                }
+            }
+            // may come before the mtllib defining it, or name one no mtllib
+            // defines, see `Obj::unknown_materials`
+            "usemtl" => material = Some(args.join(" ")),
+            "mtllib" => {
+                if args.is_empty() {
+                    return Err(error(String::from("mtllib needs a file name")));
+                }
+                for name in &args {
+                    materials.extend(mtl::load(base.join(name))?);
+                }
+            }
+            // smoothing groups, lines, points and free-form geometry
+            _ => {}
+        }
+    }
+
+    Ok(Obj {
+        meshes: builders
+            .into_iter()
+            .map(|b| b.build(&positions, &uvs, &normals))
+            .collect(),
+        materials,
+    })
+}
+
+// `v`, `v/vt`, `v//vn` or `v/vt/vn`, one based, negative counting back from
+// the last element read so far.
+fn face_vertex(
+    token: &str,
+    positions: usize,
+    uvs: usize,
+    normals: usize,
+) -> Result<VertexKey, String> {
+    let mut fields = token.split('/');
+    let position = match fields.next() {
+        Some(p) if !p.is_empty() => index(p, positions, "position")?,
+        _ => return Err(format!("face vertex {:?} has no position", token)),
+    };
+    let uv = match fields.next() {
+        Some(t) if !t.is_empty() => Some(index(t, uvs, "texture coordinate")?),
+        _ => None,
+    };
+    let normal = match fields.next() {
+        Some(n) if !n.is_empty() => Some(index(n, normals, "normal")?),
+        _ => None,
+    };
+    if fields.next().is_some() {
+        return Err(format!("face vertex {:?} has too many fields", token));
+    }
+    Ok((position, uv, normal))
+}
+
+fn index(value: &str, count: usize, what: &str) -> Result<usize, String> {
+    let i: i64 = value
+        .parse()
+        .map_err(|_| format!("invalid {} index {:?}", what, value))?;
+    let resolved = if i > 0 { i - 1 } else { count as i64 + i };
+    if i == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(format!(
+            "{} index {} is out of range, {} defined so far",
+            what, i, count
+        ));
+    }
+    Ok(resolved as usize)
+}
+
+fn floats(keyword: &str, args: &[&str], min: usize, max: usize) -> Result<Vec<f32>, String> {
+    if args.len() < min || args.len() > max {
+        return Err(if min == max {
+            format!("{} expects {} numbers, got {}", keyword, min, args.len())
+        } else {
+            format!(
+                "{} expects {} to {} numbers, got {}",
+                keyword,
+                min,
+                max,
+                args.len()
+            )
+        });
+    }
+    args.iter()
+        .map(|a| match a.parse::<f32>() {
+            Ok(v) if v.is_finite() => Ok(v),
+            _ => Err(format!("invalid number {:?} in {}", a, keyword)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn parse_str(source: &str) -> Result<Obj, LoadError> {
+        parse("test.obj", source, Path::new(""))
+    }
+
+    fn error_at(source: &str) -> (Option<usize>, String) {
+        match parse_str(source) {
+            Ok(_) => panic!("{:?} parsed", source),
+            Err(e) => (e.line, e.message),
+        }
+    }
+
+    #[test]
+    fn resolves_negative_indices_against_what_was_read_so_far() {
+        let obj =
+            parse_str("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\nv 5 5 5\nf -4 -3 -1\n").unwrap();
+        let data = &obj.meshes[0].data;
+        assert_eq!(data.indices, vec![[0, 1, 2], [0, 1, 3]]);
+        assert_eq!(data.positions[3].x, 5.);
+    }
+
+    #[test]
+    fn reads_normals_without_uvs() {
+        let obj = parse_str("v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2//1 3//-1\n").unwrap();
+        let data = &obj.meshes[0].data;
+        assert!(data.uvs.is_none());
+        let normals = data.normals.as_ref().unwrap();
+        assert_eq!(normals.len(), 3);
+        assert!(normals.iter().all(|n| n.z == 1.));
+    }
+
+    #[test]
+    fn triangulates_polygons_as_fans() {
+        let obj = parse_str("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n").unwrap();
+        assert_eq!(obj.meshes[0].data.indices, vec![[0, 1, 2], [0, 2, 3]]);
+    }
+
+    #[test]
+    fn rejects_out_of_range_indices_with_their_line() {
+        let (line, message) = error_at("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n");
+        assert_eq!(line, Some(4));
+        assert!(
+            message.contains("position index 4 is out of range"),
+            "{}",
+            message
+        );
+
+        let (line, message) = error_at("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -4 -2 -1\n");
+        assert_eq!(line, Some(4));
+        assert!(message.contains("position index -4"), "{}", message);
+
+        let (_, message) = error_at("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n");
+        assert!(message.contains("position index 0"), "{}", message);
+
+        let (_, message) = error_at("v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1//2 2//1 3//1\n");
+        assert!(message.contains("normal index 2"), "{}", message);
+
+        let (_, message) = error_at("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1/1 2/1 3/1\n");
+        assert!(
+            message.contains("texture coordinate index 1"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn unknown_materials_are_checked_against_every_mtllib() {
+        let dir = env::temp_dir().join(format!("obj-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.mtl"), "newmtl red\nKd 1 0 0\n").unwrap();
+        let source = "usemtl red\nmtllib a.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n\
+                      usemtl ghost\nf 1 2 3\nusemtl ghost\nf 3 2 1\n";
+        let obj = parse("test.obj", source, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(obj.unwrap().unknown_materials(), vec!["ghost"]);
+    }
+}
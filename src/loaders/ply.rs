@@ -0,0 +1,522 @@
+use crate::hittable::Hittable;
+use crate::loaders::LoadError;
+use crate::materials::lambertian::Lambertian;
+use crate::materials::material::Material;
+use crate::primitives::triangle_mesh::{MeshData, TriangleMesh};
+use crate::scene::WorldItem;
+use crate::textures::vertex_colors::VertexColors;
+use crate::tonemap::srgb_decode;
+use crate::vec3::{Color, Point3, Vec3};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+// A Stanford PLY mesh, with colors when the vertices carry red, green and
+// blue.
+pub struct Ply {
+    pub data: MeshData,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Type {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+enum Property {
+    Scalar(String, Type),
+    List(String, Type, Type),
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Ply {
+    // A single world item. Without an explicit material the mesh is
+    // diffuse, colored by its vertices when it has colors.
+    pub fn into_world(self, material: Option<Arc<dyn Material + Sync + Send>>) -> WorldItem {
+        let material = material.unwrap_or_else(|| match &self.data.colors {
+            Some(_) => Arc::new(Lambertian::textured(Arc::new(VertexColors))),
+            None => Arc::new(Lambertian::new(Color::new(0.5))),
+        });
+        let mesh = TriangleMesh::new(self.data).expect("ply faces are checked on load");
+        (Arc::new(mesh) as Arc<dyn Hittable + Sync + Send>, material)
+    }
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Ply, LoadError> {
+    let file = path.as_ref().display().to_string();
+    let bytes = fs::read(&path)
+        .map_err(|e| LoadError::new(&file, None, format!("could not read file: {}", e)))?;
+    parse(&file, &bytes)
+}
+
+pub fn parse(file: &str, bytes: &[u8]) -> Result<Ply, LoadError> {
+    let (format, elements, body) = header(file, bytes)?;
+    let error = |message: String| LoadError::new(file, None, message);
+
+    let mut reader = Reader {
+        format,
+        bytes: body,
+        offset: 0,
+        tokens: if format == Format::Ascii {
+            String::from_utf8_lossy(body)
+                .split_whitespace()
+                .map(String::from)
+                .collect()
+        } else {
+            vec![]
+        },
+    };
+
+    let mut positions = vec![];
+    let mut normals = vec![];
+    let mut colors = vec![];
+    let mut uvs = vec![];
+    let mut indices = vec![];
+    let mut has = (false, false, false);
+
+    for element in &elements {
+        match element.name.as_str() {
+            "vertex" => {
+                let names: Vec<&str> = element
+                    .properties
+                    .iter()
+                    .map(|p| match p {
+                        Property::Scalar(name, _) | Property::List(name, _, _) => name.as_str(),
+                    })
+                    .collect();
+                let find = |candidates: &[&str]| {
+                    candidates
+                        .iter()
+                        .find_map(|c| names.iter().position(|n| n == c))
+                };
+                let xyz = [find(&["x"]), find(&["y"]), find(&["z"])];
+                if xyz.iter().any(|i| i.is_none()) {
+                    return Err(error(String::from("vertex element needs x, y and z")));
+                }
+                let normal = [find(&["nx"]), find(&["ny"]), find(&["nz"])];
+                let rgb = [
+                    find(&["red", "diffuse_red", "r"]),
+                    find(&["green", "diffuse_green", "g"]),
+                    find(&["blue", "diffuse_blue", "b"]),
+                ];
+                let uv = [
+                    find(&["u", "s", "texture_u", "texture_s"]),
+                    find(&["v", "t", "texture_v", "texture_t"]),
+                ];
+                has = (
+                    normal.iter().all(|i| i.is_some()),
+                    rgb.iter().all(|i| i.is_some()),
+                    uv.iter().all(|i| i.is_some()),
+                );
+
+                for _ in 0..element.count {
+                    let mut values = Vec::with_capacity(element.properties.len());
+                    for property in &element.properties {
+                        match property {
+                            Property::Scalar(_, ty) => {
+                                values.push(reader.read(*ty).map_err(error)?)
+                            }
+                            Property::List(_, count, item) => {
+                                reader.skip_list(*count, *item).map_err(error)?;
+                                values.push(0.);
+                            }
+                        }
+                    }
+                    let get = |i: Option<usize>| values[i.unwrap()];
+                    positions.push(Point3 {
+                        x: get(xyz[0]) as f32,
+                        y: get(xyz[1]) as f32,
+                        z: get(xyz[2]) as f32,
+                    });
+                    if has.0 {
+                        normals.push(Vec3 {
+                            x: get(normal[0]) as f32,
+                            y: get(normal[1]) as f32,
+                            z: get(normal[2]) as f32,
+                        });
+                    }
+                    if has.1 {
+                        let channel = |i: Option<usize>| {
+                            let ty = match &element.properties[i.unwrap()] {
+                                Property::Scalar(_, ty) => *ty,
+                                Property::List(_, _, ty) => *ty,
+                            };
+                            color_channel(get(i), ty)
+                        };
+                        colors.push(Color {
+                            x: channel(rgb[0]),
+                            y: channel(rgb[1]),
+                            z: channel(rgb[2]),
+                        });
+                    }
+                    if has.2 {
+                        uvs.push([get(uv[0]) as f32, get(uv[1]) as f32]);
+                    }
+                }
+            }
+            "face" => {
+                for face in 0..element.count {
+                    for property in &element.properties {
+                        match property {
+                            Property::List(name, count, item)
+                                if name == "vertex_indices" || name == "vertex_index" =>
+                            {
+                                let polygon = reader.read_list(*count, *item).map_err(error)?;
+                                if polygon.len() < 3 {
+                                    return Err(error(format!(
+                                        "face {} has {} vertices, at least 3 are needed",
+                                        face,
+                                        polygon.len()
+                                    )));
+                                }
+                                let polygon = polygon
+                                    .iter()
+                                    .map(|&i| vertex_index(i, positions.len(), face))
+                                    .collect::<Result<Vec<usize>, String>>()
+                                    .map_err(error)?;
+                                // fan triangulation
+                                for pair in polygon[1..].windows(2) {
+                                    indices.push([polygon[0], pair[0], pair[1]]);
+                                }
+                            }
+                            Property::List(_, count, item) => {
+                                reader.skip_list(*count, *item).map_err(error)?
+                            }
+                            Property::Scalar(_, ty) => {
+                                reader.read(*ty).map_err(error)?;
+                            }
+                        }
+                    }
+                }
+            }
+            // anything else, edges or materials, is read past
+            _ => {
+                for _ in 0..element.count {
+                    for property in &element.properties {
+                        match property {
+                            Property::List(_, count, item) => {
+                                reader.skip_list(*count, *item).map_err(error)?
+                            }
+                            Property::Scalar(_, ty) => {
+                                reader.read(*ty).map_err(error)?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if indices.is_empty() {
+        return Err(error(String::from(
+            "no faces, point clouds are not supported",
+        )));
+    }
+
+    Ok(Ply {
+        data: MeshData {
+            positions,
+            indices,
+            normals: if has.0 { Some(normals) } else { None },
+            uvs: if has.2 { Some(uvs) } else { None },
+            colors: if has.1 { Some(colors) } else { None },
+        },
+    })
+}
+
+fn header<'a>(file: &str, bytes: &'a [u8]) -> Result<(Format, Vec<Element>, &'a [u8]), LoadError> {
+    const END: &[u8] = b"end_header";
+    let end = bytes
+        .windows(END.len())
+        .position(|w| w == END)
+        .ok_or_else(|| LoadError::new(file, None, String::from("missing end_header")))?;
+    // the body starts after the newline ending the end_header line
+    let body_start = bytes[end..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |i| end + i + 1);
+    let text = String::from_utf8_lossy(&bytes[..end]);
+
+    let mut format = None;
+    let mut elements: Vec<Element> = vec![];
+    for (number, line) in text.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| LoadError::new(file, Some(number), message);
+        let parts: Vec<&str> = line.split_whitespace().collect();
+
+        match parts.as_slice() {
+            [] => {}
+            ["ply"] if number == 1 => {}
+            _ if number == 1 => return Err(error(String::from("not a PLY file"))),
+            ["format", kind, version] => {
+                if !version.starts_with('1') {
+                    return Err(error(format!("unsupported PLY version {}", version)));
+                }
+                format = Some(match *kind {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::LittleEndian,
+                    "binary_big_endian" => Format::BigEndian,
+                    _ => return Err(error(format!("unknown format {}", kind))),
+                });
+            }
+            ["comment", ..] | ["obj_info", ..] => {}
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| error(format!("invalid element count {:?}", count)))?,
+                properties: vec![],
+            }),
+            ["property", "list", count, item, name] => {
+                let property = Property::List(
+                    name.to_string(),
+                    scalar_type(count).map_err(error)?,
+                    scalar_type(item).map_err(error)?,
+                );
+                elements
+                    .last_mut()
+                    .ok_or_else(|| error(String::from("property before the first element")))?
+                    .properties
+                    .push(property);
+            }
+            ["property", ty, name] => {
+                let property = Property::Scalar(name.to_string(), scalar_type(ty).map_err(error)?);
+                elements
+                    .last_mut()
+                    .ok_or_else(|| error(String::from("property before the first element")))?
+                    .properties
+                    .push(property);
+            }
+            _ => return Err(error(format!("unexpected header line {:?}", line))),
+        }
+    }
+
+    let format =
+        format.ok_or_else(|| LoadError::new(file, None, String::from("missing format line")))?;
+    Ok((format, elements, &bytes[body_start..]))
+}
+
+fn scalar_type(name: &str) -> Result<Type, String> {
+    match name {
+        "char" | "int8" => Ok(Type::I8),
+        "uchar" | "uint8" => Ok(Type::U8),
+        "short" | "int16" => Ok(Type::I16),
+        "ushort" | "uint16" => Ok(Type::U16),
+        "int" | "int32" => Ok(Type::I32),
+        "uint" | "uint32" => Ok(Type::U32),
+        "float" | "float32" => Ok(Type::F32),
+        "double" | "float64" => Ok(Type::F64),
+        _ => Err(format!("unknown property type {}", name)),
+    }
+}
+
+// Integer channels are 8 or 16 bit sRGB, float channels are taken as linear.
+fn color_channel(value: f64, ty: Type) -> f32 {
+    match ty {
+        Type::U8 | Type::I8 => srgb_decode(value as f32 / 255.),
+        Type::U16 | Type::I16 => srgb_decode(value as f32 / 65535.),
+        Type::I32 | Type::U32 => srgb_decode(value as f32 / 255.),
+        Type::F32 | Type::F64 => value as f32,
+    }
+}
+
+fn vertex_index(value: f64, count: usize, face: usize) -> Result<usize, String> {
+    if value < 0. || value >= count as f64 {
+        return Err(format!(
+            "face {} refers to vertex {}, only {} defined",
+            face, value, count
+        ));
+    }
+    Ok(value as usize)
+}
+
+struct Reader<'a> {
+    format: Format,
+    bytes: &'a [u8],
+    offset: usize,
+    tokens: Vec<String>,
+}
+
+impl Reader<'_> {
+    fn read(&mut self, ty: Type) -> Result<f64, String> {
+        if self.format == Format::Ascii {
+            let token = self
+                .tokens
+                .get(self.offset)
+                .ok_or_else(|| String::from("unexpected end of file"))?;
+            self.offset += 1;
+            return token
+                .parse()
+                .map_err(|_| format!("invalid number {:?}", token));
+        }
+
+        let size = match ty {
+            Type::I8 | Type::U8 => 1,
+            Type::I16 | Type::U16 => 2,
+            Type::I32 | Type::U32 | Type::F32 => 4,
+            Type::F64 => 8,
+        };
+        let raw = self
+            .bytes
+            .get(self.offset..self.offset + size)
+            .ok_or_else(|| String::from("unexpected end of file"))?;
+        self.offset += size;
+
+        let mut buf = [0u8; 8];
+        buf[..size].copy_from_slice(raw);
+        if self.format == Format::BigEndian {
+            buf[..size].reverse();
+        }
+        Ok(match ty {
+            Type::I8 => buf[0] as i8 as f64,
+            Type::U8 => buf[0] as f64,
+            Type::I16 => i16::from_le_bytes([buf[0], buf[1]]) as f64,
+            Type::U16 => u16::from_le_bytes([buf[0], buf[1]]) as f64,
+            Type::I32 => i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+            Type::U32 => u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+            Type::F32 => f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+            Type::F64 => f64::from_le_bytes(buf),
+        })
+    }
+
+    fn read_list(&mut self, count: Type, item: Type) -> Result<Vec<f64>, String> {
+        let n = self.read(count)?;
+        if n < 0. {
+            return Err(format!("negative list length {}", n));
+        }
+        (0..n as usize).map(|_| self.read(item)).collect()
+    }
+
+    fn skip_list(&mut self, count: Type, item: Type) -> Result<(), String> {
+        self.read_list(count, item).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "element vertex 3\n\
+                          property float x\n\
+                          property float y\n\
+                          property float z\n\
+                          property uchar red\n\
+                          property uchar green\n\
+                          property uchar blue\n\
+                          element face 1\n\
+                          property list uchar int vertex_indices\n\
+                          end_header\n";
+
+    // The same triangle in either byte order: red, green and blue corners.
+    fn binary(format: &str, big_endian: bool) -> Vec<u8> {
+        let mut bytes = format!("ply\nformat {} 1.0\n{}", format, HEADER).into_bytes();
+        let vertices = [
+            ([0f32, 0., 0.], [255u8, 0, 0]),
+            ([1., 0., 0.], [0, 255, 0]),
+            ([0., 1., 0.], [0, 0, 255]),
+        ];
+        for (position, color) in vertices {
+            for v in position {
+                if big_endian {
+                    bytes.extend(v.to_be_bytes());
+                } else {
+                    bytes.extend(v.to_le_bytes());
+                }
+            }
+            bytes.extend(color);
+        }
+        bytes.push(3);
+        for i in [0i32, 1, 2] {
+            if big_endian {
+                bytes.extend(i.to_be_bytes());
+            } else {
+                bytes.extend(i.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    fn parse_err(bytes: &[u8]) -> String {
+        match parse("test.ply", bytes) {
+            Ok(_) => panic!("parsed"),
+            Err(e) => e.message,
+        }
+    }
+
+    #[test]
+    fn reads_big_endian_binary() {
+        let ply = parse("test.ply", &binary("binary_big_endian", true)).unwrap();
+        let data = &ply.data;
+        assert_eq!(data.indices, vec![[0, 1, 2]]);
+        assert_eq!((data.positions[1].x, data.positions[2].y), (1., 1.));
+        let colors = data.colors.as_ref().unwrap();
+        assert_eq!((colors[0].x, colors[0].y, colors[0].z), (1., 0., 0.));
+        assert_eq!((colors[2].x, colors[2].y, colors[2].z), (0., 0., 1.));
+
+        let little = parse("test.ply", &binary("binary_little_endian", false)).unwrap();
+        for (a, b) in data.positions.iter().zip(&little.data.positions) {
+            assert_eq!((a.x, a.y, a.z), (b.x, b.y, b.z));
+        }
+        assert_eq!(little.data.indices, data.indices);
+    }
+
+    #[test]
+    fn reads_ascii_with_polygons() {
+        let source = "ply\nformat ascii 1.0\ncomment a quad\n\
+                      element vertex 4\nproperty float x\nproperty float y\nproperty float z\n\
+                      element face 1\nproperty list uchar int vertex_index\nend_header\n\
+                      0 0 0\n1 0 0\n1 1 0\n0 1 0\n4 0 1 2 3\n";
+        let ply = parse("test.ply", source.as_bytes()).unwrap();
+        assert_eq!(ply.data.indices, vec![[0, 1, 2], [0, 2, 3]]);
+        assert!(ply.data.colors.is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let whole = binary("binary_big_endian", true);
+        // inside the vertices, then inside the face list
+        for cut in [whole.len() - 20, whole.len() - 2] {
+            assert_eq!(parse_err(&whole[..cut]), "unexpected end of file");
+        }
+
+        let ascii = format!("ply\nformat ascii 1.0\n{}0 0 0 255 0 0\n1 0 0\n", HEADER);
+        assert_eq!(parse_err(ascii.as_bytes()), "unexpected end of file");
+
+        let header_only = format!("ply\nformat ascii 1.0\n{}", HEADER);
+        let cut = header_only.find("end_header").unwrap();
+        assert_eq!(
+            parse_err(&header_only.as_bytes()[..cut]),
+            "missing end_header"
+        );
+    }
+
+    #[test]
+    fn rejects_faces_past_the_last_vertex() {
+        let source = format!(
+            "ply\nformat ascii 1.0\n{}0 0 0 0 0 0\n1 0 0 0 0 0\n0 1 0 0 0 0\n3 0 1 3\n",
+            HEADER
+        );
+        assert_eq!(
+            parse_err(source.as_bytes()),
+            "face 0 refers to vertex 3, only 3 defined"
+        );
+    }
+}
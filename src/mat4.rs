@@ -0,0 +1,188 @@
+use crate::vec3::{Math, Point3, Vec3};
+use std::ops::Mul;
+
+// Row major 4x4 matrix acting on column vectors, so `a * b` applies `b`
+// first.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Mat4 {
+    pub m: [[f32; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn identity() -> Self {
+        Self {
+            m: [
+                [1., 0., 0., 0.],
+                [0., 1., 0., 0.],
+                [0., 0., 1., 0.],
+                [0., 0., 0., 1.],
+            ],
+        }
+    }
+
+    pub fn from_rows(m: [[f32; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    // The column major layout used by glTF and OpenGL.
+    pub fn from_cols(values: &[f32; 16]) -> Self {
+        let mut m = [[0.; 4]; 4];
+        for (col, column) in values.chunks(4).enumerate() {
+            for (row, value) in column.iter().enumerate() {
+                m[row][col] = *value;
+            }
+        }
+        Self { m }
+    }
+
+    pub fn translation(offset: Vec3) -> Self {
+        let mut t = Self::identity();
+        t.m[0][3] = offset.x;
+        t.m[1][3] = offset.y;
+        t.m[2][3] = offset.z;
+        t
+    }
+
+    pub fn scaling(factor: Vec3) -> Self {
+        let mut s = Self::identity();
+        s.m[0][0] = factor.x;
+        s.m[1][1] = factor.y;
+        s.m[2][2] = factor.z;
+        s
+    }
+
+    // Counter clockwise by `degrees` looking down `axis` towards the origin.
+    pub fn rotation(axis: Vec3, degrees: f32) -> Self {
+        let Vec3 { x, y, z } = axis.unit();
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let t = 1. - cos;
+        Self::from_rows([
+            [
+                t * x * x + cos,
+                t * x * y - sin * z,
+                t * x * z + sin * y,
+                0.,
+            ],
+            [
+                t * x * y + sin * z,
+                t * y * y + cos,
+                t * y * z - sin * x,
+                0.,
+            ],
+            [
+                t * x * z - sin * y,
+                t * y * z + sin * x,
+                t * z * z + cos,
+                0.,
+            ],
+            [0., 0., 0., 1.],
+        ])
+    }
+
+    // A unit quaternion stored as [x, y, z, w].
+    pub fn from_quaternion(q: [f32; 4]) -> Self {
+        let [x, y, z, w] = q;
+        Self::from_rows([
+            [
+                1. - 2. * (y * y + z * z),
+                2. * (x * y - z * w),
+                2. * (x * z + y * w),
+                0.,
+            ],
+            [
+                2. * (x * y + z * w),
+                1. - 2. * (x * x + z * z),
+                2. * (y * z - x * w),
+                0.,
+            ],
+            [
+                2. * (x * z - y * w),
+                2. * (y * z + x * w),
+                1. - 2. * (x * x + y * y),
+                0.,
+            ],
+            [0., 0., 0., 1.],
+        ])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = [[0.; 4]; 4];
+        for (row, values) in self.m.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                t[col][row] = *value;
+            }
+        }
+        Self { m: t }
+    }
+
+    // Gauss-Jordan elimination with partial pivoting, None when singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Self::identity().m;
+
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1. / a[col][col];
+            for k in 0..4 {
+                a[col][k] *= scale;
+                inv[col][k] *= scale;
+            }
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+                let factor = a[row][col];
+                for k in 0..4 {
+                    a[row][k] -= factor * a[col][k];
+                    inv[row][k] -= factor * inv[col][k];
+                }
+            }
+        }
+        Some(Self { m: inv })
+    }
+
+    pub fn transform_point(&self, p: Point3) -> Point3 {
+        self.transform_vector(p)
+            + Vec3 {
+                x: self.m[0][3],
+                y: self.m[1][3],
+                z: self.m[2][3],
+            }
+    }
+
+    // Directions ignore the translation.
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3 {
+            x: m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            y: m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            z: m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        }
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, other: Mat4) -> Mat4 {
+        let mut m = [[0.; 4]; 4];
+        for (row, values) in m.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[row][k] * other.m[k][col]).sum();
+            }
+        }
+        Mat4 { m }
+    }
+}
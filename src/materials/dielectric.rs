@@ -1,6 +1,7 @@
 use crate::hittable::HitRecord;
 use crate::materials::material::Material;
 use crate::ray::Ray;
+use crate::sampler::Sampler;
 use crate::vec3::Color;
 use crate::vec3::Math;
 use crate::vec3::{Vec3, Vec3Attributes};
@@ -19,6 +20,7 @@ impl Material for Dialectric {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut Sampler,
     ) -> bool {
         *attenuation = Color::new(1.);
         let refraction_ratio = if rec.front_face {
@@ -35,9 +37,8 @@ impl Material for Dialectric {
         let sin_theta = (1. - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0;
-        let mut rng = rand::thread_rng();
         let direction = if cannot_refract
-            || reflectance(*cos_theta, refraction_ratio) > rng.gen_range(0. ..1.)
+            || reflectance(*cos_theta, refraction_ratio) > sampler.gen_range(0. ..1.)
         {
             unit_direction.reflect(rec.normal)
         } else {
@@ -46,11 +47,11 @@ impl Material for Dialectric {
 
         *scattered = Ray {
             origin: rec.p,
-            direction: direction,
-            time: r_in.time
+            direction,
+            time: r_in.time,
         };
 
-        return true;
+        true
     }
 }
 
@@ -61,7 +62,7 @@ fn refract(uv: &Vec3, n: &Vec3, etai_over_etat: f32) -> Vec3 {
     let cos_theta = cos_theta_col.first().unwrap();
     let r_out_perp = Vec3::new(etai_over_etat) * (*uv + Vec3::new(*cos_theta) * *n);
     let r_out_parallel = Vec3::new(-((1.0 - r_out_perp.length_squared()).abs()).sqrt()) * *n;
-    return r_out_perp + r_out_parallel;
+    r_out_perp + r_out_parallel
 }
 
 fn reflectance(cosine: f32, ref_idx: f32) -> f32 {
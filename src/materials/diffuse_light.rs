@@ -0,0 +1,42 @@
+use crate::hittable::HitRecord;
+use crate::materials::material::Material;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vec3::{Color, Point3};
+use std::sync::Arc;
+
+// An emitter that absorbs everything that hits it. `emit` is radiance, so
+// values above one are what make small lights bright enough to see by.
+#[derive(Clone)]
+pub struct DiffuseLight {
+    pub emit: Arc<dyn Texture + Sync + Send>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self::textured(Arc::new(SolidColor::new(emit)))
+    }
+
+    pub fn textured(emit: Arc<dyn Texture + Sync + Send>) -> Self {
+        Self { emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(
+        &self,
+        _r_in: &Ray,
+        _rec: &HitRecord,
+        _attenuation: &mut Color,
+        _scattered: &mut Ray,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        false
+    }
+
+    fn emitted(&self, u: f32, v: f32, p: &Point3) -> Color {
+        self.emit.value(u, v, p)
+    }
+}
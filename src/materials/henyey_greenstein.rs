@@ -0,0 +1,68 @@
+use crate::hittable::HitRecord;
+use crate::materials::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vec3::{Color, Vec3};
+use rand::Rng;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+// Henyey-Greenstein phase function for media. The anisotropy `g` in (-1, 1)
+// is the mean cosine of the scattering angle: positive values scatter
+// forwards like haze and clouds, negative ones back towards the light, and
+// zero is the same as `Isotropic`.
+#[derive(Clone)]
+pub struct HenyeyGreenstein {
+    pub albedo: Arc<dyn Texture + Sync + Send>,
+    pub g: f32,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: Color, g: f32) -> Self {
+        Self::textured(Arc::new(SolidColor::new(albedo)), g)
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture + Sync + Send>, g: f32) -> Self {
+        Self { albedo, g }
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+        sampler: &mut Sampler,
+    ) -> bool {
+        // invert the cumulative distribution of the scattering angle
+        let g = self.g;
+        let xi = sampler.gen::<f32>();
+        let cos_theta = if g.abs() < 1e-3 {
+            1. - 2. * xi
+        } else {
+            let s = (1. - g * g) / (1. - g + 2. * g * xi);
+            ((1. + g * g - s * s) / (2. * g)).clamp(-1., 1.)
+        };
+        let sin_theta = (1. - cos_theta * cos_theta).max(0.).sqrt();
+        let (sin_phi, cos_phi) = (2. * PI * sampler.gen::<f32>()).sin_cos();
+
+        // around the direction the ray was travelling in
+        let frame = Onb::from_w(r_in.direction);
+        *scattered = Ray {
+            origin: rec.p,
+            direction: frame.local(Vec3 {
+                x: sin_theta * cos_phi,
+                y: sin_theta * sin_phi,
+                z: cos_theta,
+            }),
+            time: r_in.time,
+        };
+        *attenuation = self.albedo.value_at(rec);
+        true
+    }
+}
@@ -0,0 +1,45 @@
+use crate::hittable::HitRecord;
+use crate::materials::material::Material;
+use crate::random_unit_vector;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vec3::Color;
+use std::sync::Arc;
+
+// Phase function of a participating medium: scatters into any direction with
+// equal probability, tinted by `albedo`.
+#[derive(Clone)]
+pub struct Isotropic {
+    pub albedo: Arc<dyn Texture + Sync + Send>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Self::textured(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture + Sync + Send>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+        sampler: &mut Sampler,
+    ) -> bool {
+        *scattered = Ray {
+            origin: rec.p,
+            direction: random_unit_vector(sampler),
+            time: r_in.time,
+        };
+        *attenuation = self.albedo.value_at(rec);
+        true
+    }
+}
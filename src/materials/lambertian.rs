@@ -2,12 +2,26 @@ use crate::hittable::HitRecord;
 use crate::materials::material::Material;
 use crate::random_unit_vector;
 use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
 use crate::vec3::Color;
 use crate::vec3::Vec3Attributes;
+use std::sync::Arc;
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct Lambertian {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture + Sync + Send>,
+}
+
+impl Lambertian {
+    pub fn new(albedo: Color) -> Self {
+        Self::textured(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture + Sync + Send>) -> Self {
+        Self { albedo }
+    }
 }
 
 impl Material for Lambertian {
@@ -17,20 +31,21 @@ impl Material for Lambertian {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut Sampler,
     ) -> bool {
         //println!("Lamb");
 
-        let mut scatter_direction = rec.normal + random_unit_vector();
+        let mut scatter_direction = rec.normal + random_unit_vector(sampler);
         if scatter_direction.near_zero() {
             scatter_direction = rec.normal;
         }
         *scattered = Ray {
             origin: rec.p,
             direction: scatter_direction,
-            time: r_in.time
+            time: r_in.time,
         };
 
-        *attenuation = self.albedo;
-        return true;
+        *attenuation = self.albedo.value_at(rec);
+        true
     }
 }
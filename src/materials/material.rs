@@ -1,6 +1,7 @@
 use crate::hittable::HitRecord;
 use crate::ray::Ray;
-use crate::vec3::Color;
+use crate::sampler::Sampler;
+use crate::vec3::{Color, Point3};
 
 pub trait Material: MatClone {
     fn scatter(
@@ -9,7 +10,12 @@ pub trait Material: MatClone {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut Sampler,
     ) -> bool;
+
+    fn emitted(&self, _u: f32, _v: f32, _p: &Point3) -> Color {
+        Color::new(0.)
+    }
 }
 
 pub trait MatClone {
@@ -2,15 +2,29 @@ use crate::hittable::HitRecord;
 use crate::materials::material::Material;
 use crate::random_in_unit_sphere;
 use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
 use crate::vec3::Color;
 use crate::vec3::{Math, Vec3Attributes};
+use std::sync::Arc;
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct Metal {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture + Sync + Send>,
     pub fuzz: f32,
 }
 
+impl Metal {
+    pub fn new(albedo: Color, fuzz: f32) -> Self {
+        Self::textured(Arc::new(SolidColor::new(albedo)), fuzz)
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture + Sync + Send>, fuzz: f32) -> Self {
+        Self { albedo, fuzz }
+    }
+}
+
 impl Material for Metal {
     fn scatter(
         &self,
@@ -18,16 +32,17 @@ impl Material for Metal {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut Sampler,
     ) -> bool {
         //println!("Metal");
         let reflected = (r_in.direction.unit()).reflect(rec.normal);
         *scattered = Ray {
             origin: rec.p,
-            direction: reflected + Color::new(self.fuzz) * random_in_unit_sphere(),
-            time: r_in.time
+            direction: reflected + Color::new(self.fuzz) * random_in_unit_sphere(sampler),
+            time: r_in.time,
         };
 
-        *attenuation = self.albedo;
-        return true;
+        *attenuation = self.albedo.value_at(rec);
+        true
     }
 }
@@ -1,4 +1,7 @@
-pub mod material;
+pub mod dielectric;
+pub mod diffuse_light;
+pub mod henyey_greenstein;
+pub mod isotropic;
 pub mod lambertian;
+pub mod material;
 pub mod metal;
-pub mod dielectric;
\ No newline at end of file
@@ -0,0 +1,46 @@
+use crate::vec3::{Math, Vec3};
+
+// Orthonormal basis with `w` along a given direction, for working in the
+// frame of a shape or a scattering event.
+#[derive(Copy, Clone, Debug)]
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    pub fn from_w(direction: Vec3) -> Self {
+        let w = direction.unit();
+        let a = if w.x.abs() > 0.9 {
+            Vec3 {
+                x: 0.,
+                y: 1.,
+                z: 0.,
+            }
+        } else {
+            Vec3 {
+                x: 1.,
+                y: 0.,
+                z: 0.,
+            }
+        };
+        let v = w.cross(a).unit();
+        let u = w.cross(v);
+        Self { u, v, w }
+    }
+
+    // From coordinates in this basis to world space.
+    pub fn local(&self, a: Vec3) -> Vec3 {
+        Vec3::new(a.x) * self.u + Vec3::new(a.y) * self.v + Vec3::new(a.z) * self.w
+    }
+
+    // From world space to coordinates in this basis.
+    pub fn to_local(&self, a: Vec3) -> Vec3 {
+        Vec3 {
+            x: a.dot(self.u),
+            y: a.dot(self.v),
+            z: a.dot(self.w),
+        }
+    }
+}
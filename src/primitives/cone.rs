@@ -0,0 +1,153 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::onb::Onb;
+use crate::primitives::disk::disk_extent;
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Point3, Vec3, Vec3Attributes};
+use std::f32::consts::PI;
+
+// A round cone narrowing from a `base` of `radius` to a point at `apex`, with
+// the base closed by a flat cap unless `capped` is false. On the side u is
+// the angle around the axis and v runs from base to apex; the cap is mapped
+// like a `Disk`.
+#[derive(Copy, Clone)]
+pub struct Cone {
+    pub base: Point3,
+    pub apex: Point3,
+    pub radius: f32,
+    pub capped: bool,
+    frame: Onb,
+    height: f32,
+}
+
+impl Cone {
+    pub fn new(base: Point3, apex: Point3, radius: f32, capped: bool) -> Self {
+        Self {
+            base,
+            apex,
+            radius,
+            capped,
+            frame: Onb::from_w(apex - base),
+            height: (apex - base).length(),
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        // In the cone's frame the axis is z from 0 to height and the side is
+        // x² + y² = (k (height - z))² with k the slope.
+        let o = self.frame.to_local(ray.origin - self.base);
+        let d = self.frame.to_local(ray.direction);
+        let (r, h) = (self.radius, self.height);
+        let k2 = (r / h) * (r / h);
+
+        let mut closest = max;
+        let mut found: Option<(Vec3, f32, f32)> = None;
+
+        let oz = h - o.z;
+        let a = d.x * d.x + d.y * d.y - k2 * d.z * d.z;
+        let half_b = o.x * d.x + o.y * d.y + k2 * oz * d.z;
+        let c = o.x * o.x + o.y * o.y - k2 * oz * oz;
+        let mut roots = [None, None];
+        if a.abs() > 1e-8 {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0. {
+                let sqrtd = discriminant.sqrt();
+                roots = [Some((-half_b - sqrtd) / a), Some((-half_b + sqrtd) / a)];
+            }
+        } else if half_b.abs() > 1e-8 {
+            // parallel to the side, crossing the cone once
+            roots[0] = Some(-c / (2. * half_b));
+        }
+        for t in roots.iter().flatten().copied() {
+            let z = o.z + t * d.z;
+            if t >= min && t < closest && (0. ..=h).contains(&z) {
+                let (x, y) = (o.x + t * d.x, o.y + t * d.y);
+                let side = Vec3 {
+                    x,
+                    y,
+                    z: k2 * (h - z),
+                };
+                // the apex has no normal of its own, it takes the axis
+                let normal = if side.length_squared() > 0. {
+                    side.unit()
+                } else {
+                    Vec3 {
+                        x: 0.,
+                        y: 0.,
+                        z: 1.,
+                    }
+                };
+                let u = (y.atan2(x) + PI) / (2. * PI);
+                closest = t;
+                found = Some((normal, u, z / h));
+            }
+        }
+
+        if self.capped && d.z.abs() > 1e-8 {
+            let t = -o.z / d.z;
+            let (x, y) = (o.x + t * d.x, o.y + t * d.y);
+            if t >= min && t < closest && x * x + y * y <= r * r {
+                let normal = Vec3 {
+                    x: 0.,
+                    y: 0.,
+                    z: -1.,
+                };
+                closest = t;
+                found = Some((normal, 0.5 * (x / r + 1.), 0.5 * (y / r + 1.)));
+            }
+        }
+
+        match found {
+            Some((normal, u, v)) => {
+                rec_out.t = closest;
+                rec_out.p = ray.at(closest);
+                rec_out.set_face_normal(ray, self.frame.local(normal));
+                rec_out.u = u;
+                rec_out.v = v;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        let extent = disk_extent(self.frame.w, self.radius);
+        *output_box = Aabb::surrounding_box(
+            Aabb::new(self.base - extent, self.base + extent),
+            Aabb::new(self.apex, self.apex),
+        )
+        .pad(1e-4);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hits_at_the_apex_have_a_normal() {
+        let axis = |x, y, z| Vec3 { x, y, z };
+        let cone = Cone::new(axis(0., 0., 0.), axis(0., 2., 0.), 1., true);
+        let ray = Ray {
+            origin: axis(0., 5., 0.),
+            direction: axis(0., -1., 0.),
+            time: 0.,
+        };
+        let mut rec = HitRecord::new();
+        assert!(cone.hit(&ray, 0.001, f32::INFINITY, &mut rec, &mut Sampler::new(0)));
+        assert!((rec.t - 3.).abs() < 1e-5);
+        let n = rec.normal;
+        assert!((n.x, n.y, n.z) == (0., 1., 0.), "{:?}", n);
+    }
+}
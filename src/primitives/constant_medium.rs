@@ -0,0 +1,93 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Vec3, Vec3Attributes};
+use rand::Rng;
+
+// A volume of uniform `density` filling a convex `boundary`, such as fog or
+// smoke. A ray travelling through it is scattered after an exponentially
+// distributed distance, or passes straight through; pair it with an
+// `Isotropic` material.
+#[derive(Clone)]
+pub struct ConstantMedium<H: Hittable> {
+    pub boundary: H,
+    pub density: f32,
+}
+
+impl<H: Hittable> ConstantMedium<H> {
+    pub fn new(boundary: H, density: f32) -> Self {
+        Self { boundary, density }
+    }
+}
+
+impl<H: Hittable + Clone + Sync + Send + 'static> Hittable for ConstantMedium<H> {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        let (t0, t1) = match inside_span(&self.boundary, ray, min, max, sampler) {
+            Some(span) => span,
+            None => return false,
+        };
+
+        let length = ray.direction.length();
+        let inside = (t1 - t0) * length;
+        let distance = -(1. - sampler.gen::<f32>()).ln() / self.density;
+        if distance > inside {
+            return false;
+        }
+
+        scatter_at(ray, t0 + distance / length, rec_out);
+        true
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        self.boundary.bounding_box(time0, time1, output_box)
+    }
+}
+
+// The part of the ray within [min, max] that lies inside `boundary`. Both
+// crossings are searched along the whole line, so a ray that starts inside
+// the volume enters it at its origin.
+pub fn inside_span<H: Hittable>(
+    boundary: &H,
+    ray: &Ray,
+    min: f32,
+    max: f32,
+    sampler: &mut Sampler,
+) -> Option<(f32, f32)> {
+    let mut enter = HitRecord::new();
+    if !boundary.hit(ray, f32::NEG_INFINITY, f32::INFINITY, &mut enter, sampler) {
+        return None;
+    }
+    let mut exit = HitRecord::new();
+    if !boundary.hit(ray, enter.t + 1e-4, f32::INFINITY, &mut exit, sampler) {
+        return None;
+    }
+
+    let t0 = enter.t.max(min).max(0.);
+    let t1 = exit.t.min(max);
+    if t0 >= t1 {
+        return None;
+    }
+    Some((t0, t1))
+}
+
+pub fn scatter_at(ray: &Ray, t: f32, rec_out: &mut HitRecord) {
+    rec_out.t = t;
+    rec_out.p = ray.at(t);
+    // arbitrary, the phase function does not look at them
+    rec_out.normal = Vec3 {
+        x: 1.,
+        y: 0.,
+        z: 0.,
+    };
+    rec_out.front_face = true;
+    rec_out.u = 0.;
+    rec_out.v = 0.;
+}
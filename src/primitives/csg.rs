@@ -0,0 +1,269 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::{Point3, Vec3};
+
+// Stops a broken, non closed operand from looping forever.
+const MAX_CROSSINGS: usize = 256;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CsgOp {
+    Union,
+    Intersection,
+    // `a` with `b` carved out of it
+    Difference,
+}
+
+impl CsgOp {
+    fn inside(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            CsgOp::Union => in_a || in_b,
+            CsgOp::Intersection => in_a && in_b,
+            CsgOp::Difference => in_a && !in_b,
+        }
+    }
+}
+
+// Boolean combination of two closed objects. Each operand's surface
+// crossings are found along the whole ray, which splits it into the
+// intervals spent inside each; sweeping the crossings in order finds where
+// the combined inside starts or stops. Surfaces of `b` bounding a difference
+// face inwards into `b`.
+//
+// Open surfaces such as a `Plane` work as half spaces, the inside being
+// behind the normal.
+#[derive(Clone)]
+pub struct Csg<A: Hittable, B: Hittable> {
+    pub op: CsgOp,
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Hittable, B: Hittable> Csg<A, B> {
+    pub fn union(a: A, b: B) -> Self {
+        Self {
+            op: CsgOp::Union,
+            a,
+            b,
+        }
+    }
+
+    pub fn intersection(a: A, b: B) -> Self {
+        Self {
+            op: CsgOp::Intersection,
+            a,
+            b,
+        }
+    }
+
+    pub fn difference(a: A, b: B) -> Self {
+        Self {
+            op: CsgOp::Difference,
+            a,
+            b,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Crossing {
+    rec: HitRecord,
+    from_a: bool,
+}
+
+impl Crossing {
+    fn entering(&self) -> bool {
+        self.rec.front_face
+    }
+
+    fn outward(&self) -> Vec3 {
+        if self.rec.front_face {
+            self.rec.normal
+        } else {
+            -self.rec.normal
+        }
+    }
+}
+
+// Every crossing of `object`'s surface along the ray, in order.
+fn crossings<H: Hittable>(
+    object: &H,
+    ray: &Ray,
+    from_a: bool,
+    out: &mut Vec<Crossing>,
+    sampler: &mut Sampler,
+) {
+    let mut t = f32::NEG_INFINITY;
+    let mut rec = HitRecord::new();
+    for _ in 0..MAX_CROSSINGS {
+        if !object.hit(ray, t, f32::INFINITY, &mut rec, sampler) {
+            break;
+        }
+        out.push(Crossing { rec, from_a });
+        t = rec.t + 1e-4 * rec.t.abs().max(1.);
+    }
+}
+
+impl<A, B> Hittable for Csg<A, B>
+where
+    A: Hittable + Clone + Sync + Send + 'static,
+    B: Hittable + Clone + Sync + Send + 'static,
+{
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        let mut all = vec![];
+        crossings(&self.a, ray, true, &mut all, sampler);
+        let a_count = all.len();
+        crossings(&self.b, ray, false, &mut all, sampler);
+
+        // A ray that first leaves an operand started inside it.
+        let mut in_a = all[..a_count].first().is_some_and(|c| !c.entering());
+        let mut in_b = all[a_count..].first().is_some_and(|c| !c.entering());
+
+        all.sort_by(|x, y| x.rec.t.total_cmp(&y.rec.t));
+        for crossing in &all {
+            let was_inside = self.op.inside(in_a, in_b);
+            if crossing.from_a {
+                in_a = crossing.entering();
+            } else {
+                in_b = crossing.entering();
+            }
+            let inside = self.op.inside(in_a, in_b);
+
+            let t = crossing.rec.t;
+            if inside == was_inside || t < min {
+                continue;
+            }
+            if t > max {
+                return false;
+            }
+
+            let outward = if !crossing.from_a && self.op == CsgOp::Difference {
+                -crossing.outward()
+            } else {
+                crossing.outward()
+            };
+            *rec_out = crossing.rec;
+            rec_out.set_face_normal(ray, outward);
+            return true;
+        }
+        false
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        let empty = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+        let (mut a, mut b) = (empty, empty);
+        let has_a = self.a.bounding_box(time0, time1, &mut a);
+        let has_b = self.b.bounding_box(time0, time1, &mut b);
+
+        *output_box = match (self.op, has_a, has_b) {
+            (CsgOp::Union, true, true) => Aabb::surrounding_box(a, b),
+            (CsgOp::Union, _, _) => return false,
+            (CsgOp::Intersection, true, true) => overlap(a, b),
+            (CsgOp::Intersection, true, false) => a,
+            (CsgOp::Intersection, false, true) => b,
+            (CsgOp::Difference, true, _) => a,
+            (_, false, _) => return false,
+        };
+        true
+    }
+}
+
+// Boxes that do not overlap give an empty box no ray can hit.
+fn overlap(a: Aabb, b: Aabb) -> Aabb {
+    let minimum = Point3 {
+        x: a.minimum.x.max(b.minimum.x),
+        y: a.minimum.y.max(b.minimum.y),
+        z: a.minimum.z.max(b.minimum.z),
+    };
+    let maximum = Point3 {
+        x: a.maximum.x.min(b.maximum.x).max(minimum.x),
+        y: a.maximum.y.min(b.maximum.y).max(minimum.y),
+        z: a.maximum.z.min(b.maximum.z).max(minimum.z),
+    };
+    Aabb::new(minimum, maximum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::sphere::Sphere;
+
+    fn point(x: f32, y: f32, z: f32) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    // Two unit spheres overlapping between x = -0.5 and x = 0.5.
+    fn pair(op: CsgOp) -> Csg<Sphere, Sphere> {
+        Csg {
+            op,
+            a: Sphere::new(point(-0.5, 0., 0.), 1.),
+            b: Sphere::new(point(0.5, 0., 0.), 1.),
+        }
+    }
+
+    // Where a ray along x from `from` first hits, and the normal's x there.
+    fn shoot<H: Hittable>(object: &H, from: Point3, dx: f32) -> Option<(f32, f32)> {
+        let ray = Ray {
+            origin: from,
+            direction: point(dx, 0., 0.),
+            time: 0.,
+        };
+        let mut rec = HitRecord::new();
+        object
+            .hit(&ray, 0.001, f32::INFINITY, &mut rec, &mut Sampler::new(0))
+            .then_some((ray.origin.x + rec.t * dx, rec.normal.x))
+    }
+
+    fn assert_hit(hit: Option<(f32, f32)>, x: f32, normal_x: f32) {
+        let (at, n) = hit.expect("missed");
+        assert!((at - x).abs() < 1e-4, "hit at x = {}, expected {}", at, x);
+        assert!(
+            (n - normal_x).abs() < 1e-4,
+            "normal x = {}, expected {}",
+            n,
+            normal_x
+        );
+    }
+
+    #[test]
+    fn union_hits_the_outer_surface() {
+        let union = pair(CsgOp::Union);
+        assert_hit(shoot(&union, point(-5., 0., 0.), 1.), -1.5, -1.);
+        assert_hit(shoot(&union, point(5., 0., 0.), -1.), 1.5, 1.);
+        // from inside the overlap the ray leaves through b
+        assert_hit(shoot(&union, point(0., 0., 0.), 1.), 1.5, -1.);
+        assert!(shoot(&union, point(-5., 2., 0.), 1.).is_none());
+    }
+
+    #[test]
+    fn intersection_hits_only_the_overlap() {
+        let intersection = pair(CsgOp::Intersection);
+        assert_hit(shoot(&intersection, point(-5., 0., 0.), 1.), -0.5, -1.);
+        assert_hit(shoot(&intersection, point(5., 0., 0.), -1.), 0.5, 1.);
+        assert!(shoot(&intersection, point(-5., 0.9, 0.), 1.).is_none());
+
+        let apart = Csg::intersection(
+            Sphere::new(point(-2., 0., 0.), 1.),
+            Sphere::new(point(2., 0., 0.), 1.),
+        );
+        assert!(shoot(&apart, point(-5., 0., 0.), 1.).is_none());
+    }
+
+    #[test]
+    fn difference_hits_the_carved_surface_facing_out() {
+        let difference = pair(CsgOp::Difference);
+        assert_hit(shoot(&difference, point(-5., 0., 0.), 1.), -1.5, -1.);
+        // through b, which is carved away, to where it left a hollow in a
+        assert_hit(shoot(&difference, point(5., 0., 0.), -1.), -0.5, 1.);
+        // inside both the ray is already outside the difference
+        assert!(shoot(&difference, point(0., 0., 0.), 1.).is_none());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::primitives::quad::Quad;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::{Point3, Vec3};
+
+// An axis aligned box between two opposite corners, made of six outward
+// facing quads.
+#[derive(Clone)]
+pub struct Cuboid {
+    sides: Vec<Quad>,
+    bounds: Aabb,
+}
+
+impl Cuboid {
+    pub fn new(a: Point3, b: Point3) -> Self {
+        let bounds = Aabb::from_points(a, b);
+        let min = bounds.minimum;
+        let max = bounds.maximum;
+
+        let dx = Vec3 {
+            x: max.x - min.x,
+            y: 0.,
+            z: 0.,
+        };
+        let dy = Vec3 {
+            x: 0.,
+            y: max.y - min.y,
+            z: 0.,
+        };
+        let dz = Vec3 {
+            x: 0.,
+            y: 0.,
+            z: max.z - min.z,
+        };
+
+        let sides = vec![
+            // front, right, back, left
+            Quad::new(Point3 { z: max.z, ..min }, dx, dy),
+            Quad::new(
+                Point3 {
+                    x: max.x,
+                    z: max.z,
+                    ..min
+                },
+                -dz,
+                dy,
+            ),
+            Quad::new(Point3 { x: max.x, ..min }, -dx, dy),
+            Quad::new(min, dz, dy),
+            // top, bottom
+            Quad::new(
+                Point3 {
+                    y: max.y,
+                    z: max.z,
+                    ..min
+                },
+                dx,
+                -dz,
+            ),
+            Quad::new(min, dx, dz),
+        ];
+
+        Self { sides, bounds }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        let mut hit_anything = false;
+        let mut closest_so_far = max;
+
+        for side in &self.sides {
+            if side.hit(ray, min, closest_so_far, rec_out, sampler) {
+                hit_anything = true;
+                closest_so_far = rec_out.t;
+            }
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        *output_box = self.bounds;
+        true
+    }
+}
@@ -0,0 +1,115 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::onb::Onb;
+use crate::primitives::disk::disk_extent;
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Point3, Vec3, Vec3Attributes};
+use std::f32::consts::PI;
+
+// A round cylinder from the center of its `base` to the center of its `top`,
+// closed by flat caps unless `capped` is false. On the side u is the angle
+// around the axis and v runs from base to top; the caps are mapped like a
+// `Disk`.
+#[derive(Copy, Clone)]
+pub struct Cylinder {
+    pub base: Point3,
+    pub top: Point3,
+    pub radius: f32,
+    pub capped: bool,
+    frame: Onb,
+    height: f32,
+}
+
+impl Cylinder {
+    pub fn new(base: Point3, top: Point3, radius: f32, capped: bool) -> Self {
+        Self {
+            base,
+            top,
+            radius,
+            capped,
+            frame: Onb::from_w(top - base),
+            height: (top - base).length(),
+        }
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        // in the cylinder's frame, the axis is z from 0 to height
+        let o = self.frame.to_local(ray.origin - self.base);
+        let d = self.frame.to_local(ray.direction);
+        let r = self.radius;
+
+        let mut closest = max;
+        let mut found: Option<(Vec3, f32, f32)> = None;
+
+        let a = d.x * d.x + d.y * d.y;
+        let half_b = o.x * d.x + o.y * d.y;
+        let c = o.x * o.x + o.y * o.y - r * r;
+        let discriminant = half_b * half_b - a * c;
+        if a > 0. && discriminant >= 0. {
+            let sqrtd = discriminant.sqrt();
+            for t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+                let z = o.z + t * d.z;
+                if t >= min && t < closest && (0. ..=self.height).contains(&z) {
+                    let (x, y) = (o.x + t * d.x, o.y + t * d.y);
+                    let normal = Vec3 {
+                        x: x / r,
+                        y: y / r,
+                        z: 0.,
+                    };
+                    let u = (y.atan2(x) + PI) / (2. * PI);
+                    closest = t;
+                    found = Some((normal, u, z / self.height));
+                    break;
+                }
+            }
+        }
+
+        if self.capped && d.z.abs() > 1e-8 {
+            for (z, facing) in [(0., -1.), (self.height, 1.)] {
+                let t = (z - o.z) / d.z;
+                let (x, y) = (o.x + t * d.x, o.y + t * d.y);
+                if t >= min && t < closest && x * x + y * y <= r * r {
+                    let normal = Vec3 {
+                        x: 0.,
+                        y: 0.,
+                        z: facing,
+                    };
+                    closest = t;
+                    found = Some((normal, 0.5 * (x / r + 1.), 0.5 * (y / r + 1.)));
+                }
+            }
+        }
+
+        match found {
+            Some((normal, u, v)) => {
+                rec_out.t = closest;
+                rec_out.p = ray.at(closest);
+                rec_out.set_face_normal(ray, self.frame.local(normal));
+                rec_out.u = u;
+                rec_out.v = v;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        let extent = disk_extent(self.frame.w, self.radius);
+        *output_box = Aabb::surrounding_box(
+            Aabb::new(self.base - extent, self.base + extent),
+            Aabb::new(self.top - extent, self.top + extent),
+        )
+        .pad(1e-4);
+        true
+    }
+}
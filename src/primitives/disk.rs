@@ -0,0 +1,75 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::onb::Onb;
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Point3, Vec3};
+
+// A flat circle facing along `normal`. (u, v) map the disk's square bounds
+// onto [0, 1], like a decal.
+#[derive(Copy, Clone)]
+pub struct Disk {
+    pub center: Point3,
+    pub radius: f32,
+    frame: Onb,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, radius: f32) -> Self {
+        Self {
+            center,
+            radius,
+            frame: Onb::from_w(normal),
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        let o = self.frame.to_local(ray.origin - self.center);
+        let d = self.frame.to_local(ray.direction);
+        // parallel to the disk
+        if d.z.abs() < 1e-8 {
+            return false;
+        }
+        let t = -o.z / d.z;
+        if t < min || max < t {
+            return false;
+        }
+        let (x, y) = (o.x + t * d.x, o.y + t * d.y);
+        if x * x + y * y > self.radius * self.radius {
+            return false;
+        }
+
+        rec_out.t = t;
+        rec_out.p = ray.at(t);
+        rec_out.set_face_normal(ray, self.frame.w);
+        rec_out.u = 0.5 * (x / self.radius + 1.);
+        rec_out.v = 0.5 * (y / self.radius + 1.);
+        true
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        let extent = disk_extent(self.frame.w, self.radius);
+        *output_box = Aabb::new(self.center - extent, self.center + extent).pad(1e-4);
+        true
+    }
+}
+
+// Half the size of the bounds of a circle of `radius` around unit `normal`,
+// along each world axis.
+pub fn disk_extent(normal: Vec3, radius: f32) -> Vec3 {
+    let extent = |n: f32| radius * (1. - n * n).max(0.).sqrt();
+    Vec3 {
+        x: extent(normal.x),
+        y: extent(normal.y),
+        z: extent(normal.z),
+    }
+}
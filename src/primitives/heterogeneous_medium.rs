@@ -0,0 +1,75 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::primitives::constant_medium::{inside_span, scatter_at};
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::Vec3Attributes;
+use crate::volumes::density::Density;
+use rand::Rng;
+use std::sync::Arc;
+
+// A medium inside a convex `boundary` whose density varies from point to
+// point: `density` times the `field`. Free flights are sampled by delta
+// tracking against the field's maximum, which stays unbiased however the
+// density varies in between. It is the only estimator; the renderer traces
+// no shadow rays, so nothing needs a transmittance estimate.
+#[derive(Clone)]
+pub struct HeterogeneousMedium<H: Hittable> {
+    pub boundary: H,
+    pub field: Arc<dyn Density + Sync + Send>,
+    pub density: f32,
+}
+
+impl<H: Hittable> HeterogeneousMedium<H> {
+    pub fn new(boundary: H, field: Arc<dyn Density + Sync + Send>, density: f32) -> Self {
+        Self {
+            boundary,
+            field,
+            density,
+        }
+    }
+
+    fn majorant(&self) -> f32 {
+        self.density * self.field.max_density()
+    }
+}
+
+impl<H: Hittable + Clone + Sync + Send + 'static> Hittable for HeterogeneousMedium<H> {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        let majorant = self.majorant();
+        if majorant <= 0. {
+            return false;
+        }
+        let (t0, t1) = match inside_span(&self.boundary, ray, min, max, sampler) {
+            Some(span) => span,
+            None => return false,
+        };
+
+        // Step through by the majorant and accept a collision as real with
+        // probability density / majorant, the rest are null collisions.
+        let length = ray.direction.length();
+        let mut t = t0;
+        loop {
+            t -= (1. - sampler.gen::<f32>()).ln() / (majorant * length);
+            if t >= t1 {
+                return false;
+            }
+            let density = self.density * self.field.density(&ray.at(t));
+            if sampler.gen::<f32>() * majorant < density {
+                scatter_at(ray, t, rec_out);
+                return true;
+            }
+        }
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        self.boundary.bounding_box(time0, time1, output_box)
+    }
+}
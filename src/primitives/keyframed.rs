@@ -0,0 +1,178 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::quaternion::Quaternion;
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Point3, Vec3, Vec3Attributes};
+
+// Poses sampled per key interval when bounding the motion.
+const BOUND_STEPS: usize = 32;
+
+// Where an object is at `time`: scaled, rotated and then translated.
+#[derive(Copy, Clone, Debug)]
+pub struct Keyframe {
+    pub time: f32,
+    pub translate: Vec3,
+    pub rotate: Quaternion,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    // Left where it is, ready to have fields set.
+    pub fn new(time: f32) -> Self {
+        Self {
+            time,
+            translate: Vec3::new(0.),
+            rotate: Quaternion::identity(),
+            scale: Vec3::new(1.),
+        }
+    }
+
+    pub fn apply(&self, p: Point3) -> Point3 {
+        self.rotate.rotate(self.scale * p) + self.translate
+    }
+}
+
+// Moves `object` through a series of keyframes, following each ray's time.
+// Between two keys translation and scale change linearly and rotation by
+// slerp, so each interval turns the short way round, at most half a turn;
+// longer spins need keys in between. Before the first key and after the last
+// the object holds still.
+#[derive(Clone)]
+pub struct Keyframed<H: Hittable> {
+    pub object: H,
+    keys: Vec<Keyframe>,
+}
+
+impl<H: Hittable> Keyframed<H> {
+    pub fn new(object: H, mut keys: Vec<Keyframe>) -> Result<Self, String> {
+        if keys.is_empty() {
+            return Err(String::from("keyframed motion needs at least one key"));
+        }
+        if let Some(key) = keys.iter().find(|k| !k.time.is_finite()) {
+            return Err(format!("key time must be finite, got {}", key.time));
+        }
+        for pair in keys.windows(2) {
+            if pair[0].time >= pair[1].time {
+                return Err(format!(
+                    "key times must increase, got {} then {}",
+                    pair[0].time, pair[1].time
+                ));
+            }
+        }
+        for key in &mut keys {
+            let Vec3 { x, y, z } = key.scale;
+            if [x, y, z].iter().any(|s| *s == 0. || !s.is_finite()) {
+                return Err(format!(
+                    "key scale must be non-zero, got [{}, {}, {}] at time {}",
+                    x, y, z, key.time
+                ));
+            }
+            key.rotate = key.rotate.normalized();
+        }
+        Ok(Self { object, keys })
+    }
+
+    pub fn keys(&self) -> &[Keyframe] {
+        &self.keys
+    }
+
+    pub fn pose(&self, time: f32) -> Keyframe {
+        let next = self.keys.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return self.keys[0];
+        }
+        if next == self.keys.len() {
+            return self.keys[next - 1];
+        }
+
+        let (a, b) = (self.keys[next - 1], self.keys[next]);
+        let s = (time - a.time) / (b.time - a.time);
+        let lerp = |from: Vec3, to: Vec3| from + Vec3::new(s) * (to - from);
+        Keyframe {
+            time,
+            translate: lerp(a.translate, b.translate),
+            rotate: a.rotate.slerp(b.rotate, s),
+            scale: lerp(a.scale, b.scale),
+        }
+    }
+}
+
+impl<H: Hittable + Clone + Sync + Send + 'static> Hittable for Keyframed<H> {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        let pose = self.pose(ray.time);
+        let inverse = pose.rotate.conjugate();
+        // not renormalized, so hit distances carry over as in `Transformed`
+        let local = Ray {
+            origin: inverse.rotate(ray.origin - pose.translate) / pose.scale,
+            direction: inverse.rotate(ray.direction) / pose.scale,
+            time: ray.time,
+        };
+        if !self.object.hit(&local, min, max, rec_out, sampler) {
+            return false;
+        }
+
+        rec_out.p = ray.at(rec_out.t);
+        rec_out.normal = pose.rotate.rotate(rec_out.normal / pose.scale).unit();
+        true
+    }
+
+    // The object's box at poses spread over the interval. A corner can only
+    // stray from the sampled positions by as far as it moves in one step, so
+    // each sample is widened by that.
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        let mut local = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+        if !self.object.bounding_box(time0, time1, &mut local) {
+            return false;
+        }
+
+        let (lo, hi) = (local.minimum, local.maximum);
+        let corners: Vec<Point3> = (0..8)
+            .map(|i| Point3 {
+                x: if i & 1 == 0 { lo.x } else { hi.x },
+                y: if i & 2 == 0 { lo.y } else { hi.y },
+                z: if i & 4 == 0 { lo.z } else { hi.z },
+            })
+            .collect();
+
+        let mut times = vec![time0];
+        times.extend(
+            self.keys
+                .iter()
+                .map(|k| k.time)
+                .filter(|t| *t > time0 && *t < time1),
+        );
+        times.push(time1);
+
+        let mut bounds: Option<Aabb> = None;
+        for pair in times.windows(2) {
+            let mut previous: Option<Vec<Point3>> = None;
+            for step in 0..=BOUND_STEPS {
+                let t = pair[0] + (pair[1] - pair[0]) * step as f32 / BOUND_STEPS as f32;
+                let pose = self.pose(t);
+                let placed: Vec<Point3> = corners.iter().map(|c| pose.apply(*c)).collect();
+                let slack = previous.as_ref().map_or(0., |previous| {
+                    placed
+                        .iter()
+                        .zip(previous)
+                        .fold(0f32, |m, (p, q)| m.max((*p - *q).length()))
+                });
+
+                for p in &placed {
+                    let b = Aabb::new(*p - Vec3::new(slack), *p + Vec3::new(slack));
+                    bounds = Some(bounds.map_or(b, |acc| Aabb::surrounding_box(acc, b)));
+                }
+                previous = Some(placed);
+            }
+        }
+        *output_box = bounds.unwrap();
+        true
+    }
+}
@@ -1,2 +1,17 @@
+pub mod cone;
+pub mod constant_medium;
+pub mod csg;
+pub mod cuboid;
+pub mod cylinder;
+pub mod disk;
+pub mod heterogeneous_medium;
+pub mod keyframed;
+pub mod moving_sphere;
+pub mod plane;
+pub mod quad;
+pub mod sdf_object;
 pub mod sphere;
-pub mod moving_sphere;
\ No newline at end of file
+pub mod torus;
+pub mod transformed;
+pub mod triangle;
+pub mod triangle_mesh;
@@ -1,5 +1,8 @@
+use crate::aabb::Aabb;
 use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::primitives::sphere::sphere_uv;
 use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
 use crate::vec3::{Math, Point3, Vec3, Vec3Attributes};
 
 #[derive(Copy, Clone)]
@@ -14,11 +17,11 @@ pub struct MovingSphere {
 impl MovingSphere {
     pub fn new(center0: Vec3, center1: Vec3, time0: f32, time1: f32, radius: f32) -> Self {
         Self {
-            center0: center0,
-            center1: center1,
-            radius: radius,
-            time0: time0,
-            time1: time1,
+            center0,
+            center1,
+            radius,
+            time0,
+            time1,
         }
     }
     pub fn center(self, time: f32) -> Point3 {
@@ -29,7 +32,14 @@ impl MovingSphere {
 }
 
 impl Hittable for MovingSphere {
-    fn hit(&self, ray: &Ray, min: f32, max: f32, rec_out: &mut HitRecord) -> bool {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
         let oc = ray.origin - self.center(ray.time);
         let a = ray.direction.length_squared();
         let half_b = oc.dot(ray.direction);
@@ -52,8 +62,25 @@ impl Hittable for MovingSphere {
 
         rec_out.t = root;
         rec_out.p = ray.at(rec_out.t);
-        let outward = (rec_out.p - self.center(ray.time)) / Vec3::new(self.radius);
+        let center = self.center(ray.time);
+        let outward = (rec_out.p - center) / Vec3::new(self.radius);
         rec_out.set_face_normal(ray, outward);
-        return true;
+        let (u, v) = sphere_uv((rec_out.p - center) / Vec3::new(self.radius.abs()));
+        rec_out.u = u;
+        rec_out.v = v;
+        true
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        let box0 = Aabb::new(
+            self.center(time0) - Vec3::new(self.radius.abs()),
+            self.center(time0) + Vec3::new(self.radius.abs()),
+        );
+        let box1 = Aabb::new(
+            self.center(time1) - Vec3::new(self.radius.abs()),
+            self.center(time1) + Vec3::new(self.radius.abs()),
+        );
+        *output_box = Aabb::surrounding_box(box0, box1);
+        true
     }
 }
@@ -0,0 +1,57 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::onb::Onb;
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Point3, Vec3};
+
+// An infinite plane through `point`, facing along `normal`. It has no
+// bounding box, so the BVH tests it against every ray. (u, v) repeat every
+// unit along the plane.
+#[derive(Copy, Clone)]
+pub struct Plane {
+    pub point: Point3,
+    frame: Onb,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3) -> Self {
+        Self {
+            point,
+            frame: Onb::from_w(normal),
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        let denom = self.frame.w.dot(ray.direction);
+        // parallel to the plane
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+        let t = self.frame.w.dot(self.point - ray.origin) / denom;
+        if t < min || max < t {
+            return false;
+        }
+
+        rec_out.t = t;
+        rec_out.p = ray.at(t);
+        rec_out.set_face_normal(ray, self.frame.w);
+        let local = self.frame.to_local(rec_out.p - self.point);
+        rec_out.u = local.x - local.x.floor();
+        rec_out.v = local.y - local.y.floor();
+        true
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, _output_box: &mut Aabb) -> bool {
+        false
+    }
+}
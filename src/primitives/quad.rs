@@ -0,0 +1,76 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Point3, Vec3};
+
+// A parallelogram with corner `q` and edges `u` and `v`. The normal follows
+// the right hand rule, u x v, and (u, v) run from 0 to 1 along the edges.
+#[derive(Copy, Clone)]
+pub struct Quad {
+    pub q: Point3,
+    pub u: Vec3,
+    pub v: Vec3,
+    normal: Vec3,
+    d: f32,
+    w: Vec3,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3) -> Self {
+        let n = u.cross(v);
+        let normal = n.unit();
+        Self {
+            q,
+            u,
+            v,
+            normal,
+            d: normal.dot(q),
+            w: n / Vec3::new(n.dot(n)),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        let denom = self.normal.dot(ray.direction);
+        // parallel to the plane
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = (self.d - self.normal.dot(ray.origin)) / denom;
+        if t < min || max < t {
+            return false;
+        }
+
+        let p = ray.at(t);
+        let planar = p - self.q;
+        let alpha = self.w.dot(planar.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar));
+        if !(0. ..=1.).contains(&alpha) || !(0. ..=1.).contains(&beta) {
+            return false;
+        }
+
+        rec_out.t = t;
+        rec_out.p = p;
+        rec_out.u = alpha;
+        rec_out.v = beta;
+        rec_out.set_face_normal(ray, self.normal);
+        true
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        let diagonal0 = Aabb::from_points(self.q, self.q + self.u + self.v);
+        let diagonal1 = Aabb::from_points(self.q + self.u, self.q + self.v);
+        *output_box = Aabb::surrounding_box(diagonal0, diagonal1).pad(1e-4);
+        true
+    }
+}
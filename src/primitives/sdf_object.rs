@@ -0,0 +1,152 @@
+use crate::aabb::{axis, Aabb};
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::primitives::sphere::sphere_uv;
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::sdf::distance::Distance;
+use crate::vec3::{Math, Point3, Vec3, Vec3Attributes};
+use std::sync::Arc;
+
+const MAX_STEPS: u32 = 512;
+// how far an unbounded field is traced
+const MAX_DISTANCE: f32 = 1e4;
+
+// The surface of a signed distance field, found by sphere tracing: the field
+// says how far the ray can safely advance, until it comes within `epsilon`
+// of the surface. Normals are the gradient of the field and (u, v) map the
+// normal like a sphere's.
+#[derive(Clone)]
+pub struct SdfObject {
+    pub field: Arc<dyn Distance + Sync + Send>,
+    pub epsilon: f32,
+    bounds: Option<Aabb>,
+}
+
+impl SdfObject {
+    pub fn new(field: Arc<dyn Distance + Sync + Send>) -> Self {
+        let bounds = field.bounds();
+        // relative to the size of the object, so fine detail stays sharp
+        let size = bounds.map_or(1., |b| (b.maximum - b.minimum).length());
+        Self {
+            field,
+            epsilon: 1e-5 * size.max(1e-3),
+            bounds,
+        }
+    }
+
+    fn normal(&self, p: Point3) -> Vec3 {
+        // central differences at the corners of a tetrahedron
+        let h = self.epsilon;
+        let corners = [
+            Vec3 {
+                x: 1.,
+                y: -1.,
+                z: -1.,
+            },
+            Vec3 {
+                x: -1.,
+                y: -1.,
+                z: 1.,
+            },
+            Vec3 {
+                x: -1.,
+                y: 1.,
+                z: -1.,
+            },
+            Vec3 {
+                x: 1.,
+                y: 1.,
+                z: 1.,
+            },
+        ];
+        corners
+            .iter()
+            .fold(Vec3::new(0.), |sum, k| {
+                sum + Vec3::new(self.field.distance(&(p + Vec3::new(h) * *k))) * *k
+            })
+            .unit()
+    }
+}
+
+impl Hittable for SdfObject {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        let (t0, t1) = match self.bounds {
+            Some(bounds) => match clip(&bounds, ray, min, max) {
+                Some(span) => span,
+                None => return false,
+            },
+            None => (min, max.min(MAX_DISTANCE)),
+        };
+
+        // March in units of distance along a unit direction. Rays leaving
+        // the surface, e.g. after refraction, start next to it, so the side
+        // the ray starts on decides the sign and the first step always moves.
+        let length = ray.direction.length();
+        let direction = ray.direction / Vec3::new(length);
+        let (start, end) = (t0 * length, t1 * length);
+        let side = if self.field.distance(&ray.at(t0)) < 0. {
+            -1.
+        } else {
+            1.
+        };
+
+        let mut s = start;
+        for step in 0..MAX_STEPS {
+            let p = ray.origin + Vec3::new(s) * direction;
+            let d = side * self.field.distance(&p);
+            let epsilon = self.epsilon * s.abs().max(1.);
+            if d < epsilon && step > 0 {
+                let t = s / length;
+                rec_out.t = t;
+                rec_out.p = p;
+                let outward = self.normal(p);
+                rec_out.set_face_normal(ray, outward);
+                let (u, v) = sphere_uv(outward);
+                rec_out.u = u;
+                rec_out.v = v;
+                return true;
+            }
+            s += d.max(epsilon);
+            if s > end {
+                return false;
+            }
+        }
+        false
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        match self.bounds {
+            Some(bounds) => {
+                *output_box = bounds.pad(1e-4);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// The part of [min, max] where the ray is inside `bounds`.
+fn clip(bounds: &Aabb, ray: &Ray, min: f32, max: f32) -> Option<(f32, f32)> {
+    let (mut t0, mut t1) = (min, max);
+    for a in 0..3 {
+        let inv_d = 1. / axis(ray.direction, a);
+        let mut near = (axis(bounds.minimum, a) - axis(ray.origin, a)) * inv_d;
+        let mut far = (axis(bounds.maximum, a) - axis(ray.origin, a)) * inv_d;
+        if inv_d < 0. {
+            std::mem::swap(&mut near, &mut far);
+        }
+        t0 = t0.max(near);
+        t1 = t1.min(far);
+        if t1 <= t0 {
+            return None;
+        }
+    }
+    Some((t0, t1))
+}
@@ -1,6 +1,9 @@
+use crate::aabb::Aabb;
 use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
 use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
 use crate::vec3::{Math, Point3, Vec3, Vec3Attributes};
+use std::f32::consts::PI;
 
 #[derive(Copy, Clone)]
 pub struct Sphere {
@@ -10,15 +13,27 @@ pub struct Sphere {
 
 impl Sphere {
     pub fn new(center: Vec3, radius: f32) -> Self {
-        Self {
-            center: center,
-            radius: radius,
-        }
+        Self { center, radius }
     }
 }
 
+// Maps a point on the unit sphere to (u, v): u is the angle around the y axis
+// starting from -x, v runs from the south pole (0) to the north pole (1).
+pub fn sphere_uv(p: Point3) -> (f32, f32) {
+    let theta = (-p.y).clamp(-1., 1.).acos();
+    let phi = (-p.z).atan2(p.x) + PI;
+    (phi / (2. * PI), theta / PI)
+}
+
 impl Hittable for Sphere {
-    fn hit(&self, ray: &Ray, min: f32, max: f32, rec_out: &mut HitRecord) -> bool {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
         let oc = ray.origin - self.center;
         let a = ray.direction.length_squared();
         let half_b = oc.dot(ray.direction);
@@ -43,6 +58,17 @@ impl Hittable for Sphere {
         rec_out.p = ray.at(rec_out.t);
         let outward = (rec_out.p - self.center) / Vec3::new(self.radius);
         rec_out.set_face_normal(ray, outward);
-        return true;
+        let (u, v) = sphere_uv((rec_out.p - self.center) / Vec3::new(self.radius.abs()));
+        rec_out.u = u;
+        rec_out.v = v;
+        true
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        *output_box = Aabb::new(
+            self.center - Vec3::new(self.radius.abs()),
+            self.center + Vec3::new(self.radius.abs()),
+        );
+        true
     }
 }
@@ -0,0 +1,108 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::onb::Onb;
+use crate::primitives::disk::disk_extent;
+use crate::ray::{Ray, RayProperties};
+use crate::roots::real_roots;
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Point3, Vec3, Vec3Attributes};
+use std::f32::consts::PI;
+
+// A ring of tube `minor` radius swept around `axis` at `major` radius from
+// `center`. u is the angle around the axis and v the angle around the tube,
+// starting from the outside.
+#[derive(Copy, Clone)]
+pub struct Torus {
+    pub center: Point3,
+    pub major: f32,
+    pub minor: f32,
+    frame: Onb,
+}
+
+impl Torus {
+    pub fn new(center: Point3, axis: Vec3, major: f32, minor: f32) -> Self {
+        Self {
+            center,
+            major,
+            minor,
+            frame: Onb::from_w(axis),
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        // The quartic is solved in f64 along a unit direction, starting from
+        // near the torus: its coefficients grow with the fourth power of the
+        // distance, and far away the roots drown in rounding.
+        let o = self.frame.to_local(ray.origin - self.center);
+        let d = self.frame.to_local(ray.direction);
+        let length = d.length() as f64;
+        let d = [
+            d.x as f64 / length,
+            d.y as f64 / length,
+            d.z as f64 / length,
+        ];
+        let mut o = [o.x as f64, o.y as f64, o.z as f64];
+        let bound = (self.major + self.minor) as f64;
+        let along = -(o[0] * d[0] + o[1] * d[1] + o[2] * d[2]);
+        let shift = (along - bound).max(0.);
+        for (o, d) in o.iter_mut().zip(d) {
+            *o += shift * d;
+        }
+
+        // |p|² + R² - r² = 2R √(x² + y²), squared
+        let (rr, r2) = ((self.major as f64).powi(2), (self.minor as f64).powi(2));
+        let m = o[0] * d[0] + o[1] * d[1] + o[2] * d[2];
+        let k = o[0] * o[0] + o[1] * o[1] + o[2] * o[2] + rr - r2;
+        let coeffs = [
+            k * k - 4. * rr * (o[0] * o[0] + o[1] * o[1]),
+            4. * m * k - 8. * rr * (o[0] * d[0] + o[1] * d[1]),
+            4. * m * m + 2. * k - 4. * rr * (d[0] * d[0] + d[1] * d[1]),
+            4. * m,
+            1.,
+        ];
+
+        let t = match real_roots(&coeffs)
+            .into_iter()
+            .map(|s| ((s + shift) / length) as f32)
+            .find(|t| *t >= min && *t <= max)
+        {
+            Some(t) => t,
+            None => return false,
+        };
+
+        let p = self.frame.to_local(ray.at(t) - self.center);
+        let ring = (p.x * p.x + p.y * p.y).sqrt();
+        let nearest = if ring > 0. {
+            Vec3 {
+                x: p.x * self.major / ring,
+                y: p.y * self.major / ring,
+                z: 0.,
+            }
+        } else {
+            Vec3::new(0.)
+        };
+        let outward = (p - nearest).unit();
+
+        rec_out.t = t;
+        rec_out.p = ray.at(t);
+        rec_out.set_face_normal(ray, self.frame.local(outward));
+        rec_out.u = (p.y.atan2(p.x) + PI) / (2. * PI);
+        rec_out.v = (p.z.atan2(ring - self.major) + PI) / (2. * PI);
+        true
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        let extent = disk_extent(self.frame.w, self.major) + Vec3::new(self.minor);
+        *output_box = Aabb::new(self.center - extent, self.center + extent);
+        true
+    }
+}
@@ -0,0 +1,83 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::mat4::Mat4;
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Point3, Vec3};
+
+// Places `object` in the world with an arbitrary affine `transform`. Rays are
+// taken into object space without renormalizing the direction, so the hit
+// distance carries over unchanged; normals come back through the inverse
+// transpose.
+#[derive(Clone)]
+pub struct Transformed<H: Hittable> {
+    pub object: H,
+    transform: Mat4,
+    inverse: Mat4,
+    normal_matrix: Mat4,
+}
+
+impl<H: Hittable> Transformed<H> {
+    pub fn new(object: H, transform: Mat4) -> Result<Self, String> {
+        let inverse = transform
+            .inverse()
+            .ok_or_else(|| String::from("transform is not invertible"))?;
+        Ok(Self {
+            object,
+            transform,
+            inverse,
+            normal_matrix: inverse.transpose(),
+        })
+    }
+
+    pub fn transform(&self) -> &Mat4 {
+        &self.transform
+    }
+}
+
+impl<H: Hittable + Clone + Sync + Send + 'static> Hittable for Transformed<H> {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        let local = Ray {
+            origin: self.inverse.transform_point(ray.origin),
+            direction: self.inverse.transform_vector(ray.direction),
+            time: ray.time,
+        };
+        if !self.object.hit(&local, min, max, rec_out, sampler) {
+            return false;
+        }
+
+        // front_face is unchanged: d . n is the same in both spaces
+        rec_out.p = ray.at(rec_out.t);
+        rec_out.normal = self.normal_matrix.transform_vector(rec_out.normal).unit();
+        true
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        let mut local = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+        if !self.object.bounding_box(time0, time1, &mut local) {
+            return false;
+        }
+
+        let (lo, hi) = (local.minimum, local.maximum);
+        let mut bounds: Option<Aabb> = None;
+        for i in 0..8 {
+            let corner = Point3 {
+                x: if i & 1 == 0 { lo.x } else { hi.x },
+                y: if i & 2 == 0 { lo.y } else { hi.y },
+                z: if i & 4 == 0 { lo.z } else { hi.z },
+            };
+            let p = self.transform.transform_point(corner);
+            let b = Aabb::from_points(p, p);
+            bounds = Some(bounds.map_or(b, |acc| Aabb::surrounding_box(acc, b)));
+        }
+        *output_box = bounds.unwrap();
+        true
+    }
+}
@@ -0,0 +1,91 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Point3};
+
+// A single triangle. (u, v) are the barycentric weights of v1 and v2.
+#[derive(Copy, Clone)]
+pub struct Triangle {
+    pub v0: Point3,
+    pub v1: Point3,
+    pub v2: Point3,
+}
+
+impl Triangle {
+    pub fn new(v0: Point3, v1: Point3, v2: Point3) -> Self {
+        Self { v0, v1, v2 }
+    }
+}
+
+// Möller–Trumbore ray/triangle intersection. Returns t and the barycentric
+// weights of v1 and v2.
+pub fn intersect(
+    ray: &Ray,
+    v0: Point3,
+    v1: Point3,
+    v2: Point3,
+    min: f32,
+    max: f32,
+) -> Option<(f32, f32, f32)> {
+    let edge1 = v1 - v0;
+    let edge2 = v2 - v0;
+    let pvec = ray.direction.cross(edge2);
+    let det = edge1.dot(pvec);
+    // parallel to the triangle's plane
+    if det == 0. {
+        return None;
+    }
+
+    let inv_det = 1. / det;
+    let tvec = ray.origin - v0;
+    let b1 = tvec.dot(pvec) * inv_det;
+    if !(0. ..=1.).contains(&b1) {
+        return None;
+    }
+
+    let qvec = tvec.cross(edge1);
+    let b2 = ray.direction.dot(qvec) * inv_det;
+    if b2 < 0. || b1 + b2 > 1. {
+        return None;
+    }
+
+    let t = edge2.dot(qvec) * inv_det;
+    if t < min || max < t {
+        return None;
+    }
+    Some((t, b1, b2))
+}
+
+pub fn bounds(v0: Point3, v1: Point3, v2: Point3) -> Aabb {
+    Aabb::surrounding_box(Aabb::from_points(v0, v1), Aabb::from_points(v2, v2)).pad(1e-4)
+}
+
+impl Hittable for Triangle {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        let (t, b1, b2) = match intersect(ray, self.v0, self.v1, self.v2, min, max) {
+            Some(hit) => hit,
+            None => return false,
+        };
+
+        rec_out.t = t;
+        rec_out.p = ray.at(t);
+        rec_out.u = b1;
+        rec_out.v = b2;
+        let outward = (self.v1 - self.v0).cross(self.v2 - self.v0).unit();
+        rec_out.set_face_normal(ray, outward);
+        true
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        *output_box = bounds(self.v0, self.v1, self.v2);
+        true
+    }
+}
@@ -0,0 +1,222 @@
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::hittable::{HitRecord, Hittable, SetFaceNormal};
+use crate::primitives::triangle::{bounds, intersect};
+use crate::ray::{Ray, RayProperties};
+use crate::sampler::Sampler;
+use crate::vec3::{Color, Math, Point3, Vec3};
+use std::sync::Arc;
+
+// Shared vertex buffers, indexed by `indices`. `normals`, `uvs` and
+// `colors`, when present, have one entry per position.
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub indices: Vec<[usize; 3]>,
+    pub normals: Option<Vec<Vec3>>,
+    pub uvs: Option<Vec<[f32; 2]>>,
+    pub colors: Option<Vec<Color>>,
+}
+
+// An indexed triangle mesh with its own BVH. The whole mesh is one object in
+// the scene, so every triangle uses the material it was added with.
+// Per-vertex normals are interpolated for shading and vertex colors are
+// passed on in the hit record for a `VertexColors` texture; without UVs the
+// barycentric coordinates are reported instead.
+#[derive(Clone)]
+pub struct TriangleMesh {
+    mesh: Arc<MeshData>,
+    bvh: Bvh,
+}
+
+#[derive(Clone)]
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    index: usize,
+}
+
+impl TriangleMesh {
+    pub fn new(mesh: MeshData) -> Result<Self, String> {
+        let count = mesh.positions.len();
+        if let Some(face) = mesh.indices.iter().find(|f| f.iter().any(|&i| i >= count)) {
+            return Err(format!(
+                "triangle {:?} refers to a vertex past the {} positions",
+                face, count
+            ));
+        }
+        if mesh.normals.as_ref().is_some_and(|n| n.len() != count) {
+            return Err(format!(
+                "mesh needs one normal for each of its {} positions",
+                count
+            ));
+        }
+        if mesh.uvs.as_ref().is_some_and(|uv| uv.len() != count) {
+            return Err(format!(
+                "mesh needs one uv for each of its {} positions",
+                count
+            ));
+        }
+        if mesh.colors.as_ref().is_some_and(|c| c.len() != count) {
+            return Err(format!(
+                "mesh needs one color for each of its {} positions",
+                count
+            ));
+        }
+
+        let mesh = Arc::new(mesh);
+        let triangles: Vec<Arc<dyn Hittable + Sync + Send>> = (0..mesh.indices.len())
+            .map(|index| {
+                Arc::new(MeshTriangle {
+                    mesh: mesh.clone(),
+                    index,
+                }) as Arc<dyn Hittable + Sync + Send>
+            })
+            .collect();
+
+        Ok(Self {
+            bvh: Bvh::new(triangles, 0., 1.),
+            mesh,
+        })
+    }
+
+    pub fn mesh(&self) -> &MeshData {
+        &self.mesh
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        self.bvh.hit(ray, min, max, rec_out, sampler)
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        self.bvh.bounding_box(time0, time1, output_box)
+    }
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> (Point3, Point3, Point3) {
+        let [a, b, c] = self.mesh.indices[self.index];
+        let p = &self.mesh.positions;
+        (p[a], p[b], p[c])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(
+        &self,
+        ray: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        let (v0, v1, v2) = self.vertices();
+        let (t, b1, b2) = match intersect(ray, v0, v1, v2, min, max) {
+            Some(hit) => hit,
+            None => return false,
+        };
+        let b0 = 1. - b1 - b2;
+        let [a, b, c] = self.mesh.indices[self.index];
+
+        rec_out.t = t;
+        rec_out.p = ray.at(t);
+        rec_out.set_face_normal(ray, (v1 - v0).cross(v2 - v0).unit());
+
+        if let Some(normals) = &self.mesh.normals {
+            let n = (Vec3::new(b0) * normals[a]
+                + Vec3::new(b1) * normals[b]
+                + Vec3::new(b2) * normals[c])
+                .unit();
+            // keep the shading normal on the side the ray came from
+            rec_out.normal = if n.dot(rec_out.normal) < 0. { -n } else { n };
+        }
+
+        match &self.mesh.uvs {
+            Some(uvs) => {
+                rec_out.u = b0 * uvs[a][0] + b1 * uvs[b][0] + b2 * uvs[c][0];
+                rec_out.v = b0 * uvs[a][1] + b1 * uvs[b][1] + b2 * uvs[c][1];
+            }
+            None => {
+                rec_out.u = b1;
+                rec_out.v = b2;
+            }
+        }
+        rec_out.color = self.mesh.colors.as_ref().map(|colors| {
+            Color::new(b0) * colors[a] + Color::new(b1) * colors[b] + Color::new(b2) * colors[c]
+        });
+        true
+    }
+
+    fn bounding_box(&self, _time0: f32, _time1: f32, output_box: &mut Aabb) -> bool {
+        let (v0, v1, v2) = self.vertices();
+        *output_box = bounds(v0, v1, v2);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f32, y: f32, z: f32) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    // A unit square in z = 0 as two triangles, with uvs matching x and y.
+    fn square() -> MeshData {
+        MeshData {
+            positions: vec![
+                point(0., 0., 0.),
+                point(1., 0., 0.),
+                point(1., 1., 0.),
+                point(0., 1., 0.),
+            ],
+            indices: vec![[0, 1, 2], [0, 2, 3]],
+            normals: None,
+            uvs: Some(vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.]]),
+            colors: None,
+        }
+    }
+
+    fn shoot(mesh: &TriangleMesh, x: f32, y: f32) -> Option<HitRecord> {
+        let ray = Ray {
+            origin: point(x, y, 1.),
+            direction: point(0., 0., -1.),
+            time: 0.,
+        };
+        let mut rec = HitRecord::new();
+        mesh.hit(&ray, 0.001, f32::INFINITY, &mut rec, &mut Sampler::new(0))
+            .then_some(rec)
+    }
+
+    #[test]
+    fn interpolates_uvs_across_both_triangles() {
+        let mesh = TriangleMesh::new(square()).unwrap();
+        for (x, y) in [(0.75, 0.25), (0.25, 0.75)] {
+            let rec = shoot(&mesh, x, y).unwrap();
+            assert!((rec.t - 1.).abs() < 1e-5);
+            assert!((rec.u - x).abs() < 1e-5 && (rec.v - y).abs() < 1e-5);
+            assert_eq!(rec.normal.z, 1.);
+        }
+        assert!(shoot(&mesh, 1.5, 0.5).is_none());
+        assert!(shoot(&mesh, -0.1, 0.5).is_none());
+    }
+
+    #[test]
+    fn rejects_indices_and_attributes_that_do_not_match_the_positions() {
+        let mut data = square();
+        data.indices.push([0, 3, 4]);
+        assert!(TriangleMesh::new(data).is_err());
+
+        let mut data = square();
+        data.uvs.as_mut().unwrap().pop();
+        assert!(TriangleMesh::new(data).is_err());
+    }
+}
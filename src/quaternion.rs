@@ -0,0 +1,156 @@
+use crate::vec3::{Math, Vec3};
+use std::ops::{Add, Mul};
+
+// A unit quaternion, for rotations that have to be blended. Interpolating
+// rotation matrices component-wise shears and shrinks whatever they carry,
+// slerp instead turns at a constant rate along the shortest arc.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quaternion {
+    pub fn identity() -> Self {
+        Self {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+            w: 1.,
+        }
+    }
+
+    // Same sense as `Mat4::rotation`.
+    pub fn from_axis_angle(axis: Vec3, degrees: f32) -> Self {
+        let Vec3 { x, y, z } = axis.unit();
+        let (sin, cos) = (degrees.to_radians() / 2.).sin_cos();
+        Self {
+            x: x * sin,
+            y: y * sin,
+            z: z * sin,
+            w: cos,
+        }
+    }
+
+    // Rotations about x, y and then z, the order instances use.
+    pub fn from_euler(degrees: Vec3) -> Self {
+        let axis = |x, y, z| Vec3 { x, y, z };
+        Self::from_axis_angle(axis(0., 0., 1.), degrees.z)
+            * Self::from_axis_angle(axis(0., 1., 0.), degrees.y)
+            * Self::from_axis_angle(axis(1., 0., 0.), degrees.x)
+    }
+
+    pub fn dot(&self, other: Quaternion) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn normalized(&self) -> Self {
+        let length = self.dot(*self).sqrt();
+        Self {
+            x: self.x / length,
+            y: self.y / length,
+            z: self.z / length,
+            w: self.w / length,
+        }
+    }
+
+    // The inverse rotation.
+    pub fn conjugate(&self) -> Self {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+            w: self.w,
+        }
+    }
+
+    pub fn rotate(&self, v: Vec3) -> Vec3 {
+        let q = Vec3 {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        };
+        let t = Vec3::new(2.) * q.cross(v);
+        v + Vec3::new(self.w) * t + q.cross(t)
+    }
+
+    // From `self` at 0 to `other` at 1, the short way round.
+    pub fn slerp(&self, other: Quaternion, t: f32) -> Self {
+        // q and -q are the same rotation, pick the nearer one
+        let (other, cos) = match self.dot(other) {
+            cos if cos < 0. => (-1. * other, -cos),
+            cos => (other, cos),
+        };
+
+        // nearly parallel, where the sine below vanishes
+        if cos > 0.9995 {
+            return (*self * (1. - t) + other * t).normalized();
+        }
+
+        let angle = cos.acos();
+        let sin = angle.sin();
+        *self * (((1. - t) * angle).sin() / sin) + other * ((t * angle).sin() / sin)
+    }
+
+    // The [x, y, z, w] layout `Mat4::from_quaternion` takes.
+    pub fn to_array(&self) -> [f32; 4] {
+        [self.x, self.y, self.z, self.w]
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+// Hamilton product, `a * b` applies `b` first.
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, b: Quaternion) -> Quaternion {
+        let a = self;
+        Quaternion {
+            x: a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            y: a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            z: a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+            w: a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+        }
+    }
+}
+
+impl Mul<f32> for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, s: f32) -> Quaternion {
+        Quaternion {
+            x: self.x * s,
+            y: self.y * s,
+            z: self.z * s,
+            w: self.w * s,
+        }
+    }
+}
+
+impl Mul<Quaternion> for f32 {
+    type Output = Quaternion;
+
+    fn mul(self, q: Quaternion) -> Quaternion {
+        q * self
+    }
+}
+
+impl Add for Quaternion {
+    type Output = Quaternion;
+
+    fn add(self, b: Quaternion) -> Quaternion {
+        Quaternion {
+            x: self.x + b.x,
+            y: self.y + b.y,
+            z: self.z + b.z,
+            w: self.w + b.w,
+        }
+    }
+}
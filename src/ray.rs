@@ -5,7 +5,7 @@ use crate::vec3::Vec3;
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
-    pub time: f32
+    pub time: f32,
 }
 
 impl Ray {
@@ -13,11 +13,17 @@ impl Ray {
         Self {
             origin: Point3::new(0.),
             direction: Vec3::new(0.),
-            time: 0.
+            time: 0.,
         }
     }
 }
 
+impl Default for Ray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub trait RayProperties {
     fn at(self, t: f32) -> Point3;
 }
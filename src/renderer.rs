@@ -0,0 +1,252 @@
+use crate::bvh::Bvh;
+use crate::camera::CameraProperties;
+use crate::framebuffer::Framebuffer;
+use crate::hittable::{HitRecord, Hittable};
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::scene::{Background, Scene, WorldItem};
+use crate::tonemap::ToneMapping;
+use crate::vec3::Color;
+use rand::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+
+#[derive(Copy, Clone, Debug)]
+pub struct RenderSettings {
+    pub image_width: u32,
+    pub image_height: u32,
+    pub samples_per_pixel: u32,
+    pub max_depth: i32,
+    pub tile_size: u32,
+    pub threads: usize,
+    pub seed: u64,
+    pub tone_mapping: ToneMapping,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            image_width: 600,
+            image_height: 400,
+            samples_per_pixel: 50,
+            max_depth: 50,
+            tile_size: 16,
+            threads: num_cpus::get(),
+            seed: 0,
+            tone_mapping: ToneMapping::default(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Tile {
+    pub x0: u32,
+    pub y0: u32,
+    pub x1: u32,
+    pub y1: u32,
+}
+
+pub fn tiles(width: u32, height: u32, tile_size: u32) -> Vec<Tile> {
+    let mut tiles = vec![];
+    for y0 in (0..height).step_by(tile_size as usize) {
+        for x0 in (0..width).step_by(tile_size as usize) {
+            tiles.push(Tile {
+                x0,
+                y0,
+                x1: (x0 + tile_size).min(width),
+                y1: (y0 + tile_size).min(height),
+            });
+        }
+    }
+    tiles
+}
+
+// Renders the image with a fixed pool of `threads` workers. Each worker pulls
+// the next tile off the shared queue, shades it with `shade(x, y)` (y grows
+// downwards) and copies the finished tile into the framebuffer. After each
+// tile `progress(finished, total)` is called with the tile counts.
+pub fn render_tiles<F, P>(
+    width: u32,
+    height: u32,
+    tile_size: u32,
+    threads: usize,
+    shade: F,
+    progress: P,
+) -> Framebuffer
+where
+    F: Fn(u32, u32) -> Color + Sync,
+    P: Fn(usize, usize) + Sync,
+{
+    let queue = tiles(width, height, tile_size.max(1));
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let framebuffer = Mutex::new(Framebuffer::new(width, height));
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                let mut pixels = vec![];
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let tile = match queue.get(index) {
+                        Some(tile) => *tile,
+                        None => break,
+                    };
+
+                    pixels.clear();
+                    for y in tile.y0..tile.y1 {
+                        for x in tile.x0..tile.x1 {
+                            pixels.push(shade(x, y));
+                        }
+                    }
+
+                    let mut fb = framebuffer.lock().unwrap();
+                    let mut it = pixels.iter();
+                    for y in tile.y0..tile.y1 {
+                        for x in tile.x0..tile.x1 {
+                            fb.set(x, y, *it.next().unwrap());
+                        }
+                    }
+                    drop(fb);
+
+                    let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    progress(finished, queue.len());
+                }
+            });
+        }
+    });
+
+    framebuffer.into_inner().unwrap()
+}
+
+pub fn render(scene: &Scene, settings: &RenderSettings) -> Framebuffer {
+    render_with_progress(scene, settings, |_, _| {})
+}
+
+// `render`, calling `progress(finished, total)` as tiles complete, from
+// whichever worker thread finished them.
+pub fn render_with_progress<P>(scene: &Scene, settings: &RenderSettings, progress: P) -> Framebuffer
+where
+    P: Fn(usize, usize) + Sync,
+{
+    let width = settings.image_width;
+    let height = settings.image_height;
+    let samples_per_pixel = settings.samples_per_pixel;
+    let (time0, time1) = scene.camera.shutter();
+    let bvh = Bvh::new(
+        scene
+            .world
+            .iter()
+            .map(|(object, _)| Arc::clone(object))
+            .collect(),
+        time0,
+        time1,
+    );
+
+    render_tiles(
+        width,
+        height,
+        settings.tile_size,
+        settings.threads,
+        |i, j| {
+            let mut pixel_color = Color::new(0.);
+            for s in 0..samples_per_pixel {
+                let mut sampler = Sampler::for_sample(settings.seed, i, j, s);
+                let u = (i as f32 + sampler.gen_range(0. ..1.)) / (width - 1) as f32;
+                let v =
+                    ((height - 1 - j) as f32 + sampler.gen_range(0. ..1.)) / (height - 1) as f32;
+                let r = scene.camera.get_ray(u, v, &mut sampler);
+                pixel_color += ray_color(
+                    r,
+                    &bvh,
+                    &scene.world,
+                    settings.max_depth,
+                    &scene.background,
+                    &mut sampler,
+                );
+            }
+
+            pixel_color / Color::new(samples_per_pixel as f32)
+        },
+        progress,
+    )
+}
+
+pub fn ray_color(
+    ray: Ray,
+    bvh: &Bvh,
+    world: &[WorldItem],
+    depth: i32,
+    background: &Background,
+    sampler: &mut Sampler,
+) -> Color {
+    let rec = &mut HitRecord::new();
+
+    if depth <= 0 {
+        return Color {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        };
+    }
+    if !bvh.hit(&ray, 0.001, f32::INFINITY, rec, sampler) {
+        return background.color(&ray);
+    }
+
+    let mut scattered = Ray::new();
+    let mut attenuation = Color::new(0.);
+    let mat = &world[rec.material_index as usize].1;
+    let emitted = mat.emitted(rec.u, rec.v, &rec.p);
+    if !mat.scatter(&ray, rec, &mut attenuation, &mut scattered, sampler) {
+        return emitted;
+    }
+
+    emitted + attenuation * ray_color(scattered, bvh, world, depth - 1, background, sampler)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenes;
+
+    fn render_with(threads: usize, tile_size: u32) -> Framebuffer {
+        let settings = RenderSettings {
+            image_width: 24,
+            image_height: 16,
+            samples_per_pixel: 4,
+            max_depth: 8,
+            tile_size,
+            threads,
+            seed: 7,
+            ..RenderSettings::default()
+        };
+        let scene = scenes::by_name("random-spheres", 1.5, settings.seed).unwrap();
+        render(&scene, &settings)
+    }
+
+    fn bits(framebuffer: &Framebuffer) -> Vec<[u32; 3]> {
+        let mut bits = vec![];
+        for y in 0..16 {
+            for x in 0..24 {
+                let c = framebuffer.get(x, y);
+                bits.push([c.x.to_bits(), c.y.to_bits(), c.z.to_bits()]);
+            }
+        }
+        bits
+    }
+
+    #[test]
+    fn fixed_seed_renders_the_same_for_any_threads_and_tiles() {
+        let reference = bits(&render_with(1, 16));
+        for (threads, tile_size) in [(2, 16), (4, 5), (3, 1), (1, 64)] {
+            assert!(
+                bits(&render_with(threads, tile_size)) == reference,
+                "render with {} threads and {} pixel tiles differs",
+                threads,
+                tile_size
+            );
+        }
+    }
+}
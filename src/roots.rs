@@ -0,0 +1,172 @@
+// Real roots of polynomials, for shapes without a closed form intersection
+// such as the torus. Coefficients run from the constant term up.
+//
+// Closed form quartic solutions lose most of their precision near double
+// roots, which shows up as speckles along silhouettes. Instead the roots of
+// the derivative split the real line into intervals on which the polynomial
+// is monotonic, so each holds at most one root, which is then found by Newton
+// iteration falling back to bisection. The derivative's roots are found the
+// same way down to a quadratic.
+pub fn real_roots(coeffs: &[f64]) -> Vec<f64> {
+    let degree = match coeffs.iter().rposition(|c| *c != 0.) {
+        Some(degree) => degree,
+        None => return vec![],
+    };
+    let c = &coeffs[..=degree];
+
+    match degree {
+        0 => vec![],
+        1 => vec![-c[0] / c[1]],
+        2 => quadratic(c[2], c[1], c[0]),
+        _ => {
+            // no root is further from zero than this
+            let bound = 1.
+                + c[..degree]
+                    .iter()
+                    .fold(0f64, |m, a| m.max((a / c[degree]).abs()));
+
+            let derivative: Vec<f64> = c
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, a)| i as f64 * a)
+                .collect();
+            let mut edges = vec![-bound];
+            edges.extend(
+                real_roots(&derivative)
+                    .into_iter()
+                    .filter(|x| x.abs() < bound),
+            );
+            edges.push(bound);
+
+            let mut roots: Vec<f64> = vec![];
+            for pair in edges.windows(2) {
+                if let Some(root) = monotonic_root(c, pair[0], pair[1]) {
+                    // a root on a shared edge is found from both sides
+                    if roots.last() != Some(&root) {
+                        roots.push(root);
+                    }
+                }
+            }
+            roots
+        }
+    }
+}
+
+// Both roots in ascending order, computed without cancellation.
+fn quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let discriminant = b * b - 4. * a * c;
+    if discriminant < 0. {
+        return vec![];
+    }
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0. {
+        // b and c are both zero
+        return vec![0.];
+    }
+    let (x0, x1) = (q / a, c / q);
+    if x0 < x1 {
+        vec![x0, x1]
+    } else {
+        vec![x1, x0]
+    }
+}
+
+fn monotonic_root(c: &[f64], mut lo: f64, mut hi: f64) -> Option<f64> {
+    let f_lo = evaluate(c, lo).0;
+    let f_hi = evaluate(c, hi).0;
+    if f_lo == 0. {
+        return Some(lo);
+    }
+    if f_hi == 0. {
+        return Some(hi);
+    }
+    if (f_lo < 0.) == (f_hi < 0.) {
+        return None;
+    }
+
+    let mut x = 0.5 * (lo + hi);
+    for _ in 0..100 {
+        let (f, df) = evaluate(c, x);
+        if f == 0. {
+            break;
+        }
+        if (f < 0.) == (f_lo < 0.) {
+            lo = x;
+        } else {
+            hi = x;
+        }
+
+        let newton = x - f / df;
+        let next = if newton > lo && newton < hi {
+            newton
+        } else {
+            0.5 * (lo + hi)
+        };
+        let done = (next - x).abs() <= 1e-12 * (1. + x.abs());
+        x = next;
+        if done {
+            break;
+        }
+    }
+    Some(x)
+}
+
+// Horner's scheme, for the value and the slope at `x`.
+fn evaluate(c: &[f64], x: f64) -> (f64, f64) {
+    c.iter()
+        .rev()
+        .fold((0., 0.), |(f, df), a| (f * x + a, df * x + f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A repeated root may come back once or once per multiplicity, so
+    // neighbours that agree are merged before comparing.
+    fn assert_roots(coeffs: &[f64], expected: &[f64]) {
+        let mut roots = real_roots(coeffs);
+        roots.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+        assert_eq!(roots.len(), expected.len(), "{:?} gave {:?}", coeffs, roots);
+        for (root, want) in roots.iter().zip(expected) {
+            assert!(
+                (root - want).abs() < 1e-6,
+                "{:?} gave {:?}, expected {:?}",
+                coeffs,
+                roots,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn finds_simple_roots_in_ascending_order() {
+        assert_roots(&[-2., 1.], &[2.]);
+        assert_roots(&[2., -3., 1.], &[1., 2.]);
+        assert_roots(&[6., -11., 6., -1.], &[1., 2., 3.]);
+        assert_roots(&[24., -50., 35., -10., 1.], &[1., 2., 3., 4.]);
+        assert_roots(&[4., 0., -5., 0., 1.], &[-2., -1., 1., 2.]);
+    }
+
+    #[test]
+    fn finds_repeated_and_nearly_repeated_roots() {
+        assert_roots(&[1., -2., 1.], &[1.]);
+        // (x - 2)^2 (x^2 - 1)
+        assert_roots(&[-4., 4., 3., -4., 1.], &[-1., 1., 2.]);
+        // (x - 1)^2 (x - 2) (x + 3)
+        assert_roots(&[-6., 13., -7., -1., 1.], &[-3., 1., 2.]);
+        assert_roots(&[1., -4., 6., -4., 1.], &[1.]);
+        assert_roots(&[1.001, -2.001, 1.], &[1., 1.001]);
+    }
+
+    #[test]
+    fn handles_no_real_roots_and_vanishing_leading_terms() {
+        assert_roots(&[1., 0., 0., 0., 1.], &[]);
+        assert_roots(&[1., 0., 1.], &[]);
+        assert_roots(&[5.], &[]);
+        assert_roots(&[0., 0., 0.], &[]);
+        assert_roots(&[-1., 0., 0., 0., 1., 0.], &[-1., 1.]);
+        assert_roots(&[0., 0., 3.], &[0.]);
+    }
+}
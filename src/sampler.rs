@@ -0,0 +1,51 @@
+use rand::{Error, RngCore};
+
+// Small splitmix64 generator. Every camera sample gets its own sampler derived
+// from (seed, pixel, sample index), so a render only depends on the seed and
+// not on how pixels were spread across threads.
+#[derive(Copy, Clone, Debug)]
+pub struct Sampler {
+    state: u64,
+}
+
+impl Sampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: mix(seed ^ 0x853c_49e6_748f_ea9b),
+        }
+    }
+
+    pub fn for_sample(seed: u64, x: u32, y: u32, sample: u32) -> Self {
+        let pixel = ((y as u64) << 32) | x as u64;
+        Self::new(mix(mix(seed) ^ pixel) ^ sample as u64)
+    }
+}
+
+impl RngCore for Sampler {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix(self.state)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,112 @@
+use crate::aabb::Aabb;
+use crate::camera::Camera;
+use crate::hittable::{HitRecord, Hittable};
+use crate::materials::material::Material;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::{Color, Math, Vec3};
+use std::sync::Arc;
+
+pub type WorldItem = (
+    Arc<dyn Hittable + Sync + Send>,
+    Arc<dyn Material + Sync + Send>,
+);
+
+// What a ray sees when it leaves the scene. `Sky` is the blue-white gradient
+// the tutorial scenes are lit by; a black `Color` leaves only the emissive
+// materials to light the scene.
+#[derive(Copy, Clone, Debug)]
+pub enum Background {
+    Sky,
+    Color(Color),
+}
+
+impl Background {
+    pub fn color(&self, ray: &Ray) -> Color {
+        match self {
+            Background::Sky => {
+                let unit_direction = ray.direction.unit();
+                let t = 0.5 * (unit_direction.y + 1.);
+                Color::new(1. - t) * Color::new(1.)
+                    + Color::new(t)
+                        * Color {
+                            x: 0.5,
+                            y: 0.7,
+                            z: 1.0,
+                        }
+            }
+            Background::Color(color) => *color,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Scene {
+    pub world: Vec<WorldItem>,
+    pub camera: Camera,
+    pub background: Background,
+}
+
+impl Scene {
+    pub fn new(camera: Camera) -> Self {
+        Self {
+            world: vec![],
+            camera,
+            background: Background::Sky,
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        object: Arc<dyn Hittable + Sync + Send>,
+        material: Arc<dyn Material + Sync + Send>,
+    ) {
+        self.world.push((object, material));
+    }
+}
+
+impl Hittable for Vec<WorldItem> {
+    fn hit(
+        &self,
+        r: &Ray,
+        min: f32,
+        max: f32,
+        rec_out: &mut HitRecord,
+        sampler: &mut Sampler,
+    ) -> bool {
+        let mut hit_anything: bool = false;
+        let mut closest_so_far = max;
+
+        let mut temp_rec = HitRecord::new();
+
+        for (i, (item, _material)) in self.iter().enumerate() {
+            if item.hit(r, min, closest_so_far, &mut temp_rec, sampler) {
+                hit_anything = true;
+                closest_so_far = temp_rec.t;
+                *rec_out = temp_rec;
+                rec_out.material_index = i as i32;
+            }
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self, time0: f32, time1: f32, output_box: &mut Aabb) -> bool {
+        let mut first_box = true;
+        let mut temp_box = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+
+        for (item, _material) in self.iter() {
+            if !item.bounding_box(time0, time1, &mut temp_box) {
+                return false;
+            }
+            *output_box = if first_box {
+                temp_box
+            } else {
+                Aabb::surrounding_box(*output_box, temp_box)
+            };
+            first_box = false;
+        }
+
+        !first_box
+    }
+}
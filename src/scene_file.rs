@@ -0,0 +1,1783 @@
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::camera::Camera;
+use crate::hittable::Hittable;
+use crate::loaders::{gltf, obj, ply};
+use crate::mat4::Mat4;
+use crate::materials::dielectric::Dialectric;
+use crate::materials::diffuse_light::DiffuseLight;
+use crate::materials::henyey_greenstein::HenyeyGreenstein;
+use crate::materials::isotropic::Isotropic;
+use crate::materials::lambertian::Lambertian;
+use crate::materials::material::Material;
+use crate::materials::metal::Metal;
+use crate::primitives::cone::Cone;
+use crate::primitives::constant_medium::ConstantMedium;
+use crate::primitives::csg::{Csg, CsgOp};
+use crate::primitives::cuboid::Cuboid;
+use crate::primitives::cylinder::Cylinder;
+use crate::primitives::disk::Disk;
+use crate::primitives::heterogeneous_medium::HeterogeneousMedium;
+use crate::primitives::keyframed::{Keyframe, Keyframed};
+use crate::primitives::moving_sphere::MovingSphere;
+use crate::primitives::plane::Plane;
+use crate::primitives::quad::Quad;
+use crate::primitives::sdf_object::SdfObject;
+use crate::primitives::sphere::Sphere;
+use crate::primitives::torus::Torus;
+use crate::primitives::transformed::Transformed;
+use crate::primitives::triangle::Triangle;
+use crate::primitives::triangle_mesh::{MeshData, TriangleMesh};
+use crate::quaternion::Quaternion;
+use crate::renderer::RenderSettings;
+use crate::sampler::Sampler;
+use crate::scene::{Background, Scene, WorldItem};
+use crate::sdf::distance::Distance;
+use crate::sdf::mandelbulb::Mandelbulb;
+use crate::sdf::operators::{Repeat, SmoothSubtraction, SmoothUnion};
+use crate::sdf::shapes;
+use crate::textures::checker::CheckerTexture;
+use crate::textures::image_texture::ImageTexture;
+use crate::textures::noise::{MarbleTexture, NoiseTexture, WoodTexture};
+use crate::textures::perlin::Perlin;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::textures::uv_debug::UvDebug;
+use crate::tonemap::Operator;
+use crate::vec3::{Color, Math, Point3, Vec3, Vec3Attributes};
+use crate::volumes::density::Density;
+use crate::volumes::noise_density::NoiseDensity;
+use crate::volumes::voxel_grid::{RawFormat, VoxelGrid};
+use serde::de::{self, EnumAccess, IntoDeserializer, MapAccess, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use toml::Spanned;
+
+// Scene files are TOML:
+//
+//   background = [0, 0, 0]  optional, a color or "sky" (the default); a top
+//                           level key, so it goes before the first table
+//
+//   [settings]              optional, overridden by command line flags
+//   width = 600
+//   aspect_ratio = 1.5
+//   samples = 50
+//   depth = 50
+//   seed = 0
+//   tonemap = "aces"        clamp, reinhard, extended-reinhard, aces, uncharted2
+//   exposure = 0.5          in stops
+//   white = 4               white point for extended-reinhard
+//
+//   [camera]
+//   lookfrom = [13, 2, 3]
+//   lookat = [0, 0, 0]
+//   vup = [0, 1, 0]         optional
+//   vfov = 20               optional
+//   aperture = 0.1          optional
+//   focus_dist = 10         optional, defaults to the lookfrom-lookat distance
+//   shutter = [0, 1]        optional, when the shutter opens and closes; ray
+//                           times are spread over it for motion blur
+//
+//   [materials.ground]      named materials can be shared between objects
+//   type = "lambertian"     lambertian, metal, dielectric, diffuse_light,
+//                           isotropic or henyey_greenstein, the last two for
+//                           media
+//   albedo = [0.5, 0.5, 0.5]
+//
+//   [materials.lamp]
+//   type = "diffuse_light"
+//   emit = [4, 4, 4]        radiance, may be larger than one
+//
+//   [materials.haze]
+//   type = "henyey_greenstein"
+//   albedo = [0.9, 0.9, 0.9]
+//   anisotropy = 0.6        in (-1, 1), positive scatters forwards
+//
+//   albedo and emit also take a texture table instead of a color:
+//   { type = "checker", scale = 1, even = [0.2, 0.3, 0.1], odd = [0.9, 0.9, 0.9] }
+//   { type = "image", path = "earth.jpg" }   relative to the scene file
+//   { type = "uv" }                          shows the surface coordinates
+//   { type = "noise", scale = 4, color = [1, 1, 1], seed = 0 }
+//   { type = "marble", scale = 4, color = [1, 1, 1], seed = 0 }
+//   { type = "wood", scale = 4, light = [0.5, 0.3, 0.15], dark = [0.25, 0.12, 0.05] }
+//
+//   [[objects]]
+//   type = "sphere"         sphere, moving_sphere, quad, box, triangle, disk,
+//                           plane, cylinder, cone, torus, mesh, obj, ply,
+//                           gltf, sdf, union, intersection, difference,
+//                           instance, motion or medium; always the first key
+//   center = [0, -1000, 0]
+//   radius = 1000
+//   material = "ground"     a material name or an inline table
+//
+//   [[objects]]
+//   type = "quad"           a parallelogram from corner q along edges u and v
+//   q = [-5, 0, -5]
+//   u = [10, 0, 0]
+//   v = [0, 0, 10]
+//   material = "ground"
+//
+//   [[objects]]
+//   type = "box"            axis aligned, between two opposite corners
+//   min = [0, 0, 0]
+//   max = [1, 2, 1]
+//   material = "ground"
+//
+//   [[objects]]
+//   type = "triangle"
+//   v0 = [0, 0, 0]
+//   v1 = [1, 0, 0]
+//   v2 = [0, 1, 0]
+//   material = "ground"
+//
+//   [[objects]]
+//   type = "cylinder"       between the centers of its two ends
+//   base = [0, 0, 0]
+//   top = [0, 2, 0]
+//   radius = 0.5
+//   capped = true           optional, false leaves an open tube
+//   material = "ground"
+//
+//   cones take base, apex, radius and capped the same way; the other shapes:
+//   { type = "disk", center = [0, 0, 0], normal = [0, 1, 0], radius = 1 }
+//   { type = "plane", point = [0, 0, 0], normal = [0, 1, 0] }    infinite
+//   { type = "torus", center = [0, 1, 0], axis = [0, 1, 0], major = 1,
+//     minor = 0.25 }        axis is optional, up by default
+//
+//   [[objects]]
+//   type = "mesh"           indexed triangles sharing their vertices
+//   positions = [[0, 0, 0], [1, 0, 0], [0, 1, 0], [1, 1, 0]]
+//   indices = [[0, 1, 2], [2, 1, 3]]
+//   normals = [...]         optional, one per position
+//   uvs = [[0, 0], ...]     optional, one per position
+//   material = "ground"
+//
+//   [[objects]]
+//   type = "obj"            a Wavefront model, relative to the scene file
+//   path = "teapot.obj"
+//   material = "ground"     optional, for faces without an mtl material
+//
+//   [[objects]]
+//   type = "ply"            a Stanford PLY mesh, relative to the scene file
+//   path = "bunny.ply"
+//   material = "ground"     optional, defaults to the vertex colors or grey
+//
+//   [[objects]]
+//   type = "gltf"           the meshes of a .gltf or .glb file, its camera is
+//   path = "room.glb"       ignored in favour of [camera]
+//   material = "ground"     optional, replaces the file's materials
+//
+//   [[objects]]
+//   type = "sdf"            a signed distance field, found by sphere tracing
+//   material = "ground"
+//   [objects.shape]
+//   type = "smooth_union"
+//   k = 0.3
+//   shapes = [
+//     { type = "sphere", center = [0, 1, 0], radius = 1 },
+//     { type = "box", center = [1, 1, 0], size = [1, 1, 1], radius = 0.1 },
+//   ]
+//
+//   sdf shapes are sphere, box (radius rounds the edges), torus{center, major,
+//   minor} around y, capsule{a, b, radius} and mandelbulb{power = 8,
+//   iterations = 12} around the origin, combined with smooth_union and
+//   smooth_subtraction (the first shape minus the others) over `k` and
+//   repeat{shape, period, copies}: copies of a shape every period, an
+//   optional number of them either side per axis, forever otherwise.
+//
+//   [[objects]]
+//   type = "difference"     union, intersection or difference of two or more
+//   material = "ground"     closed objects, a difference being the first minus
+//   [[objects.operands]]    all the others. The operands' materials are not
+//   type = "box"            used and may be left out.
+//   min = [-1, 0, -1]
+//   max = [1, 2, 1]
+//   [[objects.operands]]
+//   type = "sphere"
+//   center = [0, 2, 0]
+//   radius = 1.2
+//
+//   [[objects]]
+//   type = "instance"       another object, scaled, then rotated about x, y and
+//   translate = [2, 0, 0]   z in turn and then translated, all optional
+//   rotate = [0, 45, 0]     in degrees
+//   scale = 2               a number or one factor per axis
+//   matrix = [[1, 0, 0, 2], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]
+//                           a row major affine matrix instead of the above
+//   [objects.object]
+//   type = "box"
+//   min = [0, 0, 0]
+//   max = [1, 1, 1]
+//   material = "ground"
+//
+//   [[objects]]
+//   type = "motion"         another object moving through keyframes, each
+//   [[objects.keys]]        posed like an instance at its time. Poses blend
+//   time = 0                in between, turning the short way round, and
+//   translate = [0, 0, 0]   hold before the first key and after the last
+//   [[objects.keys]]
+//   time = 0.5
+//   translate = [1, 0, 0]
+//   rotate = [0, 90, 0]
+//   [[objects.keys]]
+//   time = 1
+//   translate = [2, 0.5, 0]
+//   rotate = [0, 180, 0]
+//   [objects.object]
+//   type = "box"
+//   min = [0, 0, 0]
+//   max = [1, 1, 1]
+//   material = "ground"
+//
+//   [[objects]]
+//   type = "medium"         fog or smoke of constant density filling a convex
+//   density = 0.5           boundary object, scattered by the boundary's
+//   [objects.boundary]      material, usually isotropic
+//   type = "sphere"
+//   center = [0, 1, 0]
+//   radius = 1
+//   material = { type = "isotropic", albedo = [1, 1, 1] }
+//
+//   A medium with a field varies in density, `density` then scales it:
+//   field = { type = "noise", scale = 2, seed = 0 }
+//   field = { type = "grid", path = "smoke.raw", resolution = [64, 64, 64],
+//             format = "u8" }       u8, u16 or f32 little endian voxels, x
+//                                   fastest, stretched over the boundary
+pub struct SceneFile {
+    pub scene: Scene,
+    pub settings: FileSettings,
+    // problems that did not stop the scene from loading, such as models
+    // naming materials they don't define
+    pub warnings: Vec<String>,
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileSettings {
+    pub width: Option<u32>,
+    pub aspect_ratio: Option<f32>,
+    pub samples: Option<u32>,
+    pub depth: Option<i32>,
+    pub seed: Option<u64>,
+    pub tonemap: Option<Operator>,
+    pub exposure: Option<f32>,
+    pub white: Option<f32>,
+}
+
+impl FileSettings {
+    pub fn apply(&self, settings: &mut RenderSettings, aspect_ratio: &mut f32) {
+        if let Some(width) = self.width {
+            settings.image_width = width;
+        }
+        if let Some(ratio) = self.aspect_ratio {
+            *aspect_ratio = ratio;
+        }
+        if let Some(samples) = self.samples {
+            settings.samples_per_pixel = samples;
+        }
+        if let Some(depth) = self.depth {
+            settings.max_depth = depth;
+        }
+        if let Some(seed) = self.seed {
+            settings.seed = seed;
+        }
+        if let Some(operator) = self.tonemap {
+            settings.tone_mapping.operator = operator;
+        }
+        if let Some(exposure) = self.exposure {
+            settings.tone_mapping.exposure = exposure;
+        }
+        if let Some(white) = self.white {
+            settings.tone_mapping.white = white;
+        }
+        settings.image_height = (settings.image_width as f32 / *aspect_ratio) as u32;
+    }
+}
+
+#[derive(Debug)]
+pub struct SceneError {
+    pub file: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub source_line: Option<String>,
+    pub message: String,
+}
+
+impl SceneError {
+    fn new(file: &str, source: &str, span: Option<Range<usize>>, message: String) -> Self {
+        let position = span.map(|span| {
+            let before = &source[..span.start.min(source.len())];
+            let line = before.matches('\n').count() + 1;
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            let text = source[line_start..]
+                .lines()
+                .next()
+                .unwrap_or("")
+                .to_string();
+            (line, before[line_start..].chars().count() + 1, text)
+        });
+
+        match position {
+            Some((line, column, text)) => Self {
+                file: file.to_string(),
+                line: Some(line),
+                column: Some(column),
+                source_line: Some(text),
+                message,
+            },
+            None => Self {
+                file: file.to_string(),
+                line: None,
+                column: None,
+                source_line: None,
+                message,
+            },
+        }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                write!(f, "{}:{}:{}: {}", self.file, line, column, self.message)?;
+                if let Some(text) = &self.source_line {
+                    write!(f, "\n{:>5} | {}", line, text)?;
+                }
+                Ok(())
+            }
+            _ => write!(f, "{}: {}", self.file, self.message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneDesc {
+    #[serde(default)]
+    settings: FileSettings,
+    background: Option<Spanned<BackgroundDesc>>,
+    camera: Spanned<CameraDesc>,
+    #[serde(default)]
+    materials: BTreeMap<String, Spanned<MaterialDesc>>,
+    #[serde(default)]
+    objects: Vec<Spanned<ObjectDesc>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BackgroundDesc {
+    Color([f32; 3]),
+    Named(String),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraDesc {
+    lookfrom: [f32; 3],
+    lookat: [f32; 3],
+    #[serde(default = "default_vup")]
+    vup: [f32; 3],
+    #[serde(default = "default_vfov")]
+    vfov: f32,
+    #[serde(default)]
+    aperture: f32,
+    focus_dist: Option<f32>,
+    #[serde(default = "default_shutter")]
+    shutter: [f32; 2],
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum MaterialDesc {
+    Lambertian {
+        albedo: TextureRef,
+    },
+    Metal {
+        albedo: TextureRef,
+        #[serde(default)]
+        fuzz: f32,
+    },
+    #[serde(alias = "dialectric")]
+    Dielectric {
+        ir: f32,
+    },
+    DiffuseLight {
+        emit: TextureRef,
+    },
+    Isotropic {
+        albedo: TextureRef,
+    },
+    HenyeyGreenstein {
+        albedo: TextureRef,
+        anisotropy: f32,
+    },
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(untagged)]
+enum TextureRef {
+    Color([f32; 3]),
+    Texture(TextureDesc),
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum TextureDesc {
+    Checker {
+        #[serde(default = "default_texture_scale")]
+        scale: f32,
+        even: Box<TextureRef>,
+        odd: Box<TextureRef>,
+    },
+    Image {
+        path: String,
+    },
+    Uv,
+    Noise {
+        #[serde(default = "default_texture_scale")]
+        scale: f32,
+        #[serde(default = "default_noise_color")]
+        color: [f32; 3],
+        #[serde(default)]
+        seed: u64,
+    },
+    Marble {
+        #[serde(default = "default_texture_scale")]
+        scale: f32,
+        #[serde(default = "default_noise_color")]
+        color: [f32; 3],
+        #[serde(default)]
+        seed: u64,
+    },
+    Wood {
+        #[serde(default = "default_texture_scale")]
+        scale: f32,
+        light: [f32; 3],
+        dark: [f32; 3],
+        #[serde(default)]
+        seed: u64,
+    },
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(untagged)]
+enum MaterialRef {
+    Named(String),
+    Inline(MaterialDesc),
+    // left out, only allowed where an enclosing object supplies one
+    #[default]
+    #[serde(skip)]
+    Inherited,
+}
+
+// Read through the `Deserialize` impl below, which takes the `type` key as
+// the variant.
+#[derive(Deserialize)]
+#[serde(remote = "Self", rename_all = "snake_case", deny_unknown_fields)]
+enum ObjectDesc {
+    Sphere {
+        center: [f32; 3],
+        radius: f32,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    MovingSphere {
+        center0: [f32; 3],
+        center1: [f32; 3],
+        #[serde(default)]
+        time0: f32,
+        #[serde(default = "default_time1")]
+        time1: f32,
+        radius: f32,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Quad {
+        q: [f32; 3],
+        u: [f32; 3],
+        v: [f32; 3],
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Box {
+        min: [f32; 3],
+        max: [f32; 3],
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Triangle {
+        v0: [f32; 3],
+        v1: [f32; 3],
+        v2: [f32; 3],
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Disk {
+        center: [f32; 3],
+        normal: [f32; 3],
+        radius: f32,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Plane {
+        point: [f32; 3],
+        normal: [f32; 3],
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Cylinder {
+        base: [f32; 3],
+        top: [f32; 3],
+        radius: f32,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Cone {
+        base: [f32; 3],
+        apex: [f32; 3],
+        radius: f32,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Torus {
+        center: [f32; 3],
+        #[serde(default = "default_vup")]
+        axis: [f32; 3],
+        major: f32,
+        minor: f32,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Mesh {
+        positions: Vec<[f32; 3]>,
+        indices: Vec<[usize; 3]>,
+        normals: Option<Vec<[f32; 3]>>,
+        uvs: Option<Vec<[f32; 2]>>,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Obj {
+        path: String,
+        material: Option<MaterialRef>,
+    },
+    Ply {
+        path: String,
+        material: Option<MaterialRef>,
+    },
+    Gltf {
+        path: String,
+        material: Option<MaterialRef>,
+    },
+    Instance {
+        object: Box<ObjectDesc>,
+        translate: Option<[f32; 3]>,
+        rotate: Option<[f32; 3]>,
+        scale: Option<ScaleDesc>,
+        matrix: Option<Spanned<[[f32; 4]; 4]>>,
+    },
+    Sdf {
+        shape: SdfDesc,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Union {
+        operands: Vec<ObjectDesc>,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Intersection {
+        operands: Vec<ObjectDesc>,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Difference {
+        operands: Vec<ObjectDesc>,
+        #[serde(default)]
+        material: MaterialRef,
+    },
+    Medium {
+        boundary: Box<ObjectDesc>,
+        density: f32,
+        field: Option<FieldDesc>,
+    },
+    Motion {
+        object: Box<ObjectDesc>,
+        keys: Vec<KeyDesc>,
+    },
+}
+
+// serde's internally tagged enums buffer the table before looking at the tag,
+// which loses the positions `Spanned` fields record. Objects instead have to
+// start with their `type`, and the rest of the table is read as that
+// variant's fields straight from the file.
+impl<'de> Deserialize<'de> for ObjectDesc {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+struct ObjectVisitor;
+
+impl<'de> Visitor<'de> for ObjectVisitor {
+    type Value = ObjectDesc;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an object table")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ObjectDesc, A::Error> {
+        match map.next_key::<String>()? {
+            Some(key) if key == "type" => {}
+            Some(key) => {
+                return Err(de::Error::custom(format!(
+                    "`type` must be the first key of an object, found `{}` before it",
+                    key
+                )))
+            }
+            None => return Err(de::Error::missing_field("type")),
+        }
+        let kind: String = map.next_value()?;
+        ObjectDesc::deserialize(TaggedObject { kind, fields: map })
+    }
+}
+
+// An object's `type` and remaining keys in the shape of an externally tagged
+// enum, which is what the derived `ObjectDesc::deserialize` reads.
+struct TaggedObject<A> {
+    kind: String,
+    fields: A,
+}
+
+impl<'de, A: MapAccess<'de>> Deserializer<'de> for TaggedObject<A> {
+    type Error = A::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, A::Error> {
+        visitor.visit_enum(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, A: MapAccess<'de>> EnumAccess<'de> for TaggedObject<A> {
+    type Error = A::Error;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), A::Error> {
+        let kind: de::value::StringDeserializer<A::Error> = self.kind.clone().into_deserializer();
+        let variant = seed.deserialize(kind)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, A: MapAccess<'de>> VariantAccess<'de> for TaggedObject<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        Err(de::Error::custom("objects are tables"))
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, _: T) -> Result<T::Value, A::Error> {
+        Err(de::Error::custom("objects are tables"))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, _: V) -> Result<V::Value, A::Error> {
+        Err(de::Error::custom("objects are tables"))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        visitor.visit_map(self.fields)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyDesc {
+    time: f32,
+    translate: Option<[f32; 3]>,
+    rotate: Option<[f32; 3]>,
+    scale: Option<ScaleDesc>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum SdfDesc {
+    Sphere {
+        center: [f32; 3],
+        radius: f32,
+    },
+    Box {
+        center: [f32; 3],
+        size: [f32; 3],
+        #[serde(default)]
+        radius: f32,
+    },
+    Torus {
+        center: [f32; 3],
+        major: f32,
+        minor: f32,
+    },
+    Capsule {
+        a: [f32; 3],
+        b: [f32; 3],
+        radius: f32,
+    },
+    SmoothUnion {
+        shapes: Vec<SdfDesc>,
+        k: f32,
+    },
+    SmoothSubtraction {
+        shapes: Vec<SdfDesc>,
+        k: f32,
+    },
+    Repeat {
+        shape: Box<SdfDesc>,
+        period: [f32; 3],
+        copies: Option<[u32; 3]>,
+    },
+    Mandelbulb {
+        #[serde(default = "default_mandelbulb_power")]
+        power: f32,
+        #[serde(default = "default_mandelbulb_iterations")]
+        iterations: u32,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum FieldDesc {
+    Noise {
+        #[serde(default = "default_texture_scale")]
+        scale: f32,
+        #[serde(default)]
+        seed: u64,
+    },
+    Grid {
+        path: String,
+        resolution: [usize; 3],
+        format: RawFormat,
+    },
+}
+
+#[derive(Copy, Clone, Deserialize)]
+#[serde(untagged)]
+enum ScaleDesc {
+    Uniform(f32),
+    Axes([f32; 3]),
+}
+
+type Materials = BTreeMap<String, Arc<dyn Material + Sync + Send>>;
+
+fn default_vup() -> [f32; 3] {
+    [0., 1., 0.]
+}
+
+fn default_vfov() -> f32 {
+    40.
+}
+
+fn default_shutter() -> [f32; 2] {
+    [0., 1.]
+}
+
+fn default_time1() -> f32 {
+    1.
+}
+
+fn default_capped() -> bool {
+    true
+}
+
+fn default_mandelbulb_power() -> f32 {
+    8.
+}
+
+fn default_mandelbulb_iterations() -> u32 {
+    12
+}
+
+fn default_texture_scale() -> f32 {
+    1.
+}
+
+fn default_noise_color() -> [f32; 3] {
+    [1., 1., 1.]
+}
+
+// `shutter`, when given, replaces the camera's before any objects are built,
+// as acceleration structures inside them are only valid for the interval
+// they were built over.
+pub fn load<P: AsRef<Path>>(path: P, shutter: Option<(f32, f32)>) -> Result<SceneFile, SceneError> {
+    let file = path.as_ref().display().to_string();
+    let source = fs::read_to_string(&path)
+        .map_err(|e| SceneError::new(&file, "", None, format!("could not read file: {}", e)))?;
+    parse(&file, &source, shutter)
+}
+
+pub fn parse(
+    file: &str,
+    source: &str,
+    shutter: Option<(f32, f32)>,
+) -> Result<SceneFile, SceneError> {
+    let error =
+        |span: Option<Range<usize>>, message: String| SceneError::new(file, source, span, message);
+
+    let desc: SceneDesc =
+        toml::from_str(source).map_err(|e| error(e.span(), e.message().to_string()))?;
+
+    let settings = desc.settings;
+    if let Some(ratio) = settings.aspect_ratio {
+        if !ratio.is_finite() || ratio <= 0. {
+            return Err(error(
+                None,
+                format!("settings.aspect_ratio must be positive, got {}", ratio),
+            ));
+        }
+    }
+
+    let defaults = RenderSettings::default();
+    let aspect_ratio = settings
+        .aspect_ratio
+        .unwrap_or(defaults.image_width as f32 / defaults.image_height as f32);
+    let mut camera = build_camera(desc.camera.get_ref(), aspect_ratio)
+        .map_err(|message| error(Some(desc.camera.span()), message))?;
+    if let Some((open, close)) = shutter {
+        camera = camera.with_shutter(open, close);
+    }
+
+    // image paths are relative to the scene file
+    let base = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+
+    let mut materials = Materials::new();
+    for (name, material) in &desc.materials {
+        let built = build_material(material.get_ref(), base)
+            .map_err(|m| error(Some(material.span()), m))?;
+        materials.insert(name.clone(), built);
+    }
+
+    let mut scene = Scene::new(camera);
+    if let Some(background) = &desc.background {
+        scene.background = build_background(background.get_ref())
+            .map_err(|message| error(Some(background.span()), message))?;
+    }
+    let context = Context {
+        materials: &materials,
+        base,
+        aspect_ratio,
+        shutter: camera.shutter(),
+        warnings: RefCell::new(vec![]),
+    };
+    for object in &desc.objects {
+        let items = build_object(object.get_ref(), None, &context)
+            .map_err(|e| error(Some(e.span.unwrap_or_else(|| object.span())), e.message))?;
+        scene.world.extend(items);
+    }
+
+    Ok(SceneFile {
+        scene,
+        settings,
+        warnings: context.warnings.into_inner(),
+    })
+}
+
+// Why an object could not be built, pointing at the part at fault when that
+// is narrower than the whole object.
+struct BuildError {
+    span: Option<Range<usize>>,
+    message: String,
+}
+
+impl From<String> for BuildError {
+    fn from(message: String) -> Self {
+        Self {
+            span: None,
+            message,
+        }
+    }
+}
+
+// What building an object needs besides its description.
+struct Context<'a> {
+    materials: &'a Materials,
+    base: &'a Path,
+    aspect_ratio: f32,
+    // when the camera's shutter is open, for bounding moving objects
+    shutter: (f32, f32),
+    warnings: RefCell<Vec<String>>,
+}
+
+fn build_object(
+    desc: &ObjectDesc,
+    inherited: Option<&Arc<dyn Material + Sync + Send>>,
+    context: &Context,
+) -> Result<Vec<WorldItem>, BuildError> {
+    let base = context.base;
+    let resolve = |material: &MaterialRef| match material {
+        MaterialRef::Named(name) => context
+            .materials
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("unknown material \"{}\"", name)),
+        MaterialRef::Inline(desc) => build_material(desc, base),
+        MaterialRef::Inherited => inherited
+            .cloned()
+            .ok_or_else(|| String::from("missing field `material`")),
+    };
+
+    match desc {
+        ObjectDesc::Sphere {
+            center,
+            radius,
+            material,
+        } => {
+            check_radius(*radius)?;
+            Ok(vec![(
+                Arc::new(Sphere::new(vec3(center), *radius)),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::MovingSphere {
+            center0,
+            center1,
+            time0,
+            time1,
+            radius,
+            material,
+        } => {
+            check_radius(*radius)?;
+            if time0 >= time1 {
+                return Err(
+                    format!("time0 ({}) must be smaller than time1 ({})", time0, time1).into(),
+                );
+            }
+            Ok(vec![(
+                Arc::new(MovingSphere::new(
+                    vec3(center0),
+                    vec3(center1),
+                    *time0,
+                    *time1,
+                    *radius,
+                )),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Quad { q, u, v, material } => {
+            if vec3(u).cross(vec3(v)).length() <= 0. {
+                return Err(String::from("quad edges u and v must not be parallel or zero").into());
+            }
+            Ok(vec![(
+                Arc::new(Quad::new(vec3(q), vec3(u), vec3(v))),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Box { min, max, material } => {
+            if (0..3).any(|i| min[i] == max[i]) {
+                return Err(format!(
+                    "box must have a non-zero size along every axis, got {:?} to {:?}",
+                    min, max
+                )
+                .into());
+            }
+            Ok(vec![(
+                Arc::new(Cuboid::new(vec3(min), vec3(max))),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Triangle {
+            v0,
+            v1,
+            v2,
+            material,
+        } => {
+            let (v0, v1, v2) = (vec3(v0), vec3(v1), vec3(v2));
+            if (v1 - v0).cross(v2 - v0).length() <= 0. {
+                return Err(String::from("triangle must not be degenerate").into());
+            }
+            Ok(vec![(
+                Arc::new(Triangle::new(v0, v1, v2)),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Disk {
+            center,
+            normal,
+            radius,
+            material,
+        } => {
+            check_direction("disk normal", normal)?;
+            check_size("disk radius", *radius)?;
+            Ok(vec![(
+                Arc::new(Disk::new(vec3(center), vec3(normal), *radius)),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Plane {
+            point,
+            normal,
+            material,
+        } => {
+            check_direction("plane normal", normal)?;
+            Ok(vec![(
+                Arc::new(Plane::new(vec3(point), vec3(normal))),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Cylinder {
+            base,
+            top,
+            radius,
+            capped,
+            material,
+        } => {
+            if base == top {
+                return Err(String::from("cylinder base and top must differ").into());
+            }
+            check_size("cylinder radius", *radius)?;
+            Ok(vec![(
+                Arc::new(Cylinder::new(vec3(base), vec3(top), *radius, *capped)),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Cone {
+            base,
+            apex,
+            radius,
+            capped,
+            material,
+        } => {
+            if base == apex {
+                return Err(String::from("cone base and apex must differ").into());
+            }
+            check_size("cone radius", *radius)?;
+            Ok(vec![(
+                Arc::new(Cone::new(vec3(base), vec3(apex), *radius, *capped)),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Torus {
+            center,
+            axis,
+            major,
+            minor,
+            material,
+        } => {
+            check_direction("torus axis", axis)?;
+            check_size("torus major radius", *major)?;
+            check_size("torus minor radius", *minor)?;
+            Ok(vec![(
+                Arc::new(Torus::new(vec3(center), vec3(axis), *major, *minor)),
+                resolve(material)?,
+            )])
+        }
+        ObjectDesc::Mesh {
+            positions,
+            indices,
+            normals,
+            uvs,
+            material,
+        } => {
+            let mesh = TriangleMesh::new(MeshData {
+                positions: positions.iter().map(vec3).collect(),
+                indices: indices.clone(),
+                normals: normals.as_ref().map(|n| n.iter().map(vec3).collect()),
+                uvs: uvs.clone(),
+                colors: None,
+            })?;
+            Ok(vec![(Arc::new(mesh), resolve(material)?)])
+        }
+        ObjectDesc::Obj { path, material } => {
+            let path = base.join(path);
+            let model = obj::load(&path).map_err(|e| e.to_string())?;
+            for name in model.unknown_materials() {
+                context.warnings.borrow_mut().push(format!(
+                    "{}: unknown material \"{}\", using the default",
+                    path.display(),
+                    name
+                ));
+            }
+            let default = match material {
+                Some(material) => resolve(material)?,
+                None => Arc::new(Lambertian::new(Color::new(0.5))),
+            };
+            Ok(model.into_world(default))
+        }
+        ObjectDesc::Ply { path, material } => {
+            let model = ply::load(base.join(path)).map_err(|e| e.to_string())?;
+            let material = match material {
+                Some(material) => Some(resolve(material)?),
+                None => None,
+            };
+            Ok(vec![model.into_world(material)])
+        }
+        ObjectDesc::Gltf { path, material } => {
+            let model =
+                gltf::load(base.join(path), context.aspect_ratio).map_err(|e| e.to_string())?;
+            let material = match material {
+                Some(material) => Some(resolve(material)?),
+                None => None,
+            };
+            Ok(model
+                .world
+                .into_iter()
+                .map(|(object, own)| (object, material.clone().unwrap_or(own)))
+                .collect())
+        }
+        ObjectDesc::Instance {
+            object,
+            translate,
+            rotate,
+            scale,
+            matrix,
+        } => {
+            let transform = build_transform(*translate, *rotate, *scale, matrix.as_ref())?;
+            build_object(object, inherited, context)?
+                .into_iter()
+                .map(|(object, material)| -> Result<WorldItem, BuildError> {
+                    Ok((Arc::new(Transformed::new(object, transform)?), material))
+                })
+                .collect()
+        }
+        ObjectDesc::Sdf { shape, material } => Ok(vec![(
+            Arc::new(SdfObject::new(build_sdf(shape)?)),
+            resolve(material)?,
+        )]),
+        ObjectDesc::Union { operands, material } => {
+            build_csg(CsgOp::Union, operands, resolve(material)?, context)
+        }
+        ObjectDesc::Intersection { operands, material } => {
+            build_csg(CsgOp::Intersection, operands, resolve(material)?, context)
+        }
+        ObjectDesc::Difference { operands, material } => {
+            build_csg(CsgOp::Difference, operands, resolve(material)?, context)
+        }
+        ObjectDesc::Motion { object, keys } => {
+            let keys = keys.iter().map(build_keyframe).collect::<Vec<_>>();
+            build_object(object, inherited, context)?
+                .into_iter()
+                .map(|(object, material)| -> Result<WorldItem, BuildError> {
+                    Ok((Arc::new(Keyframed::new(object, keys.clone())?), material))
+                })
+                .collect()
+        }
+        ObjectDesc::Medium {
+            boundary,
+            density,
+            field,
+        } => {
+            if !density.is_finite() || *density <= 0. {
+                return Err(format!("medium density must be positive, got {}", density).into());
+            }
+            build_object(boundary, inherited, context)?
+                .into_iter()
+                .map(|(object, material)| -> Result<WorldItem, BuildError> {
+                    let medium: Arc<dyn Hittable + Sync + Send> = match field {
+                        Some(field) => {
+                            let field = build_field(field, &object, context)?;
+                            Arc::new(HeterogeneousMedium::new(object, field, *density))
+                        }
+                        None => Arc::new(ConstantMedium::new(object, *density)),
+                    };
+                    Ok((medium, material))
+                })
+                .collect()
+        }
+    }
+}
+
+// Folds the operands from the left, so a difference is the first minus all
+// the others. Operands made of several objects, such as a model, act as
+// their union.
+fn build_csg(
+    op: CsgOp,
+    operands: &[ObjectDesc],
+    material: Arc<dyn Material + Sync + Send>,
+    context: &Context,
+) -> Result<Vec<WorldItem>, BuildError> {
+    if operands.len() < 2 {
+        return Err(format!(
+            "csg objects need at least two operands, got {}",
+            operands.len()
+        )
+        .into());
+    }
+
+    let mut shapes = operands.iter().map(|desc| {
+        let mut items = build_object(desc, Some(&material), context)?;
+        let shape: Arc<dyn Hittable + Sync + Send> = if items.len() == 1 {
+            items.remove(0).0
+        } else {
+            let (open, close) = context.shutter;
+            Arc::new(Bvh::new(
+                items.into_iter().map(|(object, _)| object).collect(),
+                open,
+                close,
+            ))
+        };
+        Ok::<_, BuildError>(shape)
+    });
+
+    let mut csg = shapes.next().unwrap()?;
+    for b in shapes {
+        csg = Arc::new(Csg { op, a: csg, b: b? });
+    }
+    Ok(vec![(csg, material)])
+}
+
+fn build_sdf(desc: &SdfDesc) -> Result<Arc<dyn Distance + Sync + Send>, String> {
+    match desc {
+        SdfDesc::Sphere { center, radius } => {
+            check_size("sdf sphere radius", *radius)?;
+            Ok(Arc::new(shapes::Sphere {
+                center: vec3(center),
+                radius: *radius,
+            }))
+        }
+        SdfDesc::Box {
+            center,
+            size,
+            radius,
+        } => {
+            for s in size {
+                check_size("sdf box size", *s)?;
+            }
+            if !radius.is_finite()
+                || *radius < 0.
+                || 2. * radius > size[0].min(size[1]).min(size[2])
+            {
+                return Err(format!(
+                    "sdf box radius must be between 0 and half the smallest size, got {}",
+                    radius
+                ));
+            }
+            Ok(Arc::new(shapes::RoundedBox {
+                center: vec3(center),
+                half_size: Vec3::new(0.5) * vec3(size),
+                radius: *radius,
+            }))
+        }
+        SdfDesc::Torus {
+            center,
+            major,
+            minor,
+        } => {
+            check_size("sdf torus major radius", *major)?;
+            check_size("sdf torus minor radius", *minor)?;
+            Ok(Arc::new(shapes::Torus {
+                center: vec3(center),
+                major: *major,
+                minor: *minor,
+            }))
+        }
+        SdfDesc::Capsule { a, b, radius } => {
+            if a == b {
+                return Err(String::from("sdf capsule ends a and b must differ"));
+            }
+            check_size("sdf capsule radius", *radius)?;
+            Ok(Arc::new(shapes::Capsule {
+                a: vec3(a),
+                b: vec3(b),
+                radius: *radius,
+            }))
+        }
+        SdfDesc::SmoothUnion { shapes, k } | SdfDesc::SmoothSubtraction { shapes, k } => {
+            check_size("smoothing k", *k)?;
+            if shapes.len() < 2 {
+                return Err(format!(
+                    "smooth operators need at least two shapes, got {}",
+                    shapes.len()
+                ));
+            }
+            let union = matches!(desc, SdfDesc::SmoothUnion { .. });
+            let mut field = build_sdf(&shapes[0])?;
+            for shape in &shapes[1..] {
+                let (a, b, k) = (field, build_sdf(shape)?, *k);
+                field = if union {
+                    Arc::new(SmoothUnion { a, b, k })
+                } else {
+                    Arc::new(SmoothSubtraction { a, b, k })
+                };
+            }
+            Ok(field)
+        }
+        SdfDesc::Repeat {
+            shape,
+            period,
+            copies,
+        } => {
+            if period.iter().any(|p| !p.is_finite() || *p < 0.) {
+                return Err(format!(
+                    "repeat period must not be negative, got {:?}",
+                    period
+                ));
+            }
+            Ok(Arc::new(Repeat {
+                shape: build_sdf(shape)?,
+                period: vec3(period),
+                copies: *copies,
+            }))
+        }
+        SdfDesc::Mandelbulb { power, iterations } => {
+            if !power.is_finite() || *power < 2. {
+                return Err(format!(
+                    "mandelbulb power must be at least 2, got {}",
+                    power
+                ));
+            }
+            Ok(Arc::new(Mandelbulb {
+                power: *power,
+                iterations: *iterations,
+            }))
+        }
+    }
+}
+
+fn build_field(
+    desc: &FieldDesc,
+    boundary: &Arc<dyn Hittable + Sync + Send>,
+    context: &Context,
+) -> Result<Arc<dyn Density + Sync + Send>, String> {
+    match desc {
+        FieldDesc::Noise { scale, seed } => {
+            if !scale.is_finite() || *scale <= 0. {
+                return Err(format!("noise field scale must be positive, got {}", scale));
+            }
+            Ok(Arc::new(NoiseDensity {
+                noise: perlin(*seed),
+                scale: *scale,
+            }))
+        }
+        FieldDesc::Grid {
+            path,
+            resolution,
+            format,
+        } => {
+            let mut bounds = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+            let (open, close) = context.shutter;
+            if !boundary.bounding_box(open, close, &mut bounds) {
+                return Err(String::from("a voxel grid needs a bounded boundary"));
+            }
+            let path = context.base.join(path);
+            let grid = VoxelGrid::load_raw(&path, bounds, *resolution, *format)
+                .map_err(|e| format!("could not load voxels {}: {}", path.display(), e))?;
+            Ok(Arc::new(grid))
+        }
+    }
+}
+
+// Scale first, then rotate about x, y and z in turn, then translate.
+// Points act on column vectors with an implicit w of one, so a matrix has to
+// be affine: a projective bottom row would silently be dropped.
+fn build_transform(
+    translate: Option<[f32; 3]>,
+    rotate: Option<[f32; 3]>,
+    scale: Option<ScaleDesc>,
+    matrix: Option<&Spanned<[[f32; 4]; 4]>>,
+) -> Result<Mat4, BuildError> {
+    if let Some(matrix) = matrix {
+        if translate.is_some() || rotate.is_some() || scale.is_some() {
+            return Err(String::from(
+                "an instance takes either a matrix or translate, rotate and scale",
+            )
+            .into());
+        }
+        let m = *matrix.get_ref();
+        let message = if m.iter().flatten().any(|v| !v.is_finite()) {
+            String::from("instance matrix must be finite")
+        } else if m[3] != [0., 0., 0., 1.] {
+            format!(
+                "instance matrix must be affine, with a last row of [0, 0, 0, 1], got {:?}",
+                m[3]
+            )
+        } else {
+            return Ok(Mat4::from_rows(m));
+        };
+        return Err(BuildError {
+            span: Some(matrix.span()),
+            message,
+        });
+    }
+
+    let scale = match scale {
+        Some(ScaleDesc::Uniform(s)) => [s; 3],
+        Some(ScaleDesc::Axes(s)) => s,
+        None => [1.; 3],
+    };
+    if scale.iter().any(|s| *s == 0. || !s.is_finite()) {
+        return Err(format!("instance scale must be non-zero, got {:?}", scale).into());
+    }
+    let [rx, ry, rz] = rotate.unwrap_or([0.; 3]);
+    let axis = |x, y, z| Vec3 { x, y, z };
+
+    Ok(Mat4::translation(vec3(&translate.unwrap_or([0.; 3])))
+        * Mat4::rotation(axis(0., 0., 1.), rz)
+        * Mat4::rotation(axis(0., 1., 0.), ry)
+        * Mat4::rotation(axis(1., 0., 0.), rx)
+        * Mat4::scaling(vec3(&scale)))
+}
+
+fn build_keyframe(desc: &KeyDesc) -> Keyframe {
+    let scale = match desc.scale {
+        Some(ScaleDesc::Uniform(s)) => [s; 3],
+        Some(ScaleDesc::Axes(s)) => s,
+        None => [1.; 3],
+    };
+    Keyframe {
+        time: desc.time,
+        translate: vec3(&desc.translate.unwrap_or([0.; 3])),
+        rotate: Quaternion::from_euler(vec3(&desc.rotate.unwrap_or([0.; 3]))),
+        scale: vec3(&scale),
+    }
+}
+
+fn build_camera(desc: &CameraDesc, aspect_ratio: f32) -> Result<Camera, String> {
+    let lookfrom = vec3(&desc.lookfrom);
+    let lookat = vec3(&desc.lookat);
+    let distance = (lookfrom - lookat).length();
+
+    if distance <= 0. {
+        return Err(String::from("camera lookfrom and lookat must differ"));
+    }
+    if desc.vfov <= 0. || desc.vfov >= 180. {
+        return Err(format!(
+            "camera vfov must be between 0 and 180 degrees, got {}",
+            desc.vfov
+        ));
+    }
+    if desc.aperture < 0. {
+        return Err(format!(
+            "camera aperture must not be negative, got {}",
+            desc.aperture
+        ));
+    }
+    let [open, close] = desc.shutter;
+    if !open.is_finite() || !close.is_finite() || open > close {
+        return Err(format!(
+            "camera shutter must open before it closes, got [{}, {}]",
+            open, close
+        ));
+    }
+
+    Ok(Camera::new(
+        lookfrom,
+        lookat,
+        vec3(&desc.vup),
+        desc.vfov,
+        aspect_ratio,
+        desc.aperture,
+        desc.focus_dist.unwrap_or(distance),
+    )
+    .with_shutter(open, close))
+}
+
+fn build_material(
+    desc: &MaterialDesc,
+    base: &Path,
+) -> Result<Arc<dyn Material + Sync + Send>, String> {
+    match desc {
+        MaterialDesc::Lambertian { albedo } => Ok(Arc::new(Lambertian::textured(build_texture(
+            "albedo", albedo, base,
+        )?))),
+        MaterialDesc::Metal { albedo, fuzz } => {
+            if *fuzz < 0. {
+                return Err(format!("metal fuzz must not be negative, got {}", fuzz));
+            }
+            Ok(Arc::new(Metal::textured(
+                build_texture("albedo", albedo, base)?,
+                *fuzz,
+            )))
+        }
+        MaterialDesc::Dielectric { ir } => {
+            if *ir <= 0. {
+                return Err(format!("dielectric ir must be positive, got {}", ir));
+            }
+            Ok(Arc::new(Dialectric { ir: *ir }))
+        }
+        MaterialDesc::DiffuseLight { emit } => Ok(Arc::new(DiffuseLight::textured(build_texture(
+            "emit", emit, base,
+        )?))),
+        MaterialDesc::Isotropic { albedo } => Ok(Arc::new(Isotropic::textured(build_texture(
+            "albedo", albedo, base,
+        )?))),
+        MaterialDesc::HenyeyGreenstein { albedo, anisotropy } => {
+            if anisotropy.is_nan() || anisotropy.abs() >= 1. {
+                return Err(format!(
+                    "henyey_greenstein anisotropy must be between -1 and 1, got {}",
+                    anisotropy
+                ));
+            }
+            Ok(Arc::new(HenyeyGreenstein::textured(
+                build_texture("albedo", albedo, base)?,
+                *anisotropy,
+            )))
+        }
+    }
+}
+
+fn build_texture(
+    name: &str,
+    desc: &TextureRef,
+    base: &Path,
+) -> Result<Arc<dyn Texture + Sync + Send>, String> {
+    match desc {
+        TextureRef::Color(c) => Ok(Arc::new(SolidColor::new(color(name, c)?))),
+        TextureRef::Texture(TextureDesc::Checker { scale, even, odd }) => {
+            check_scale(*scale)?;
+            Ok(Arc::new(CheckerTexture::new(
+                *scale,
+                build_texture(name, even, base)?,
+                build_texture(name, odd, base)?,
+            )))
+        }
+        TextureRef::Texture(TextureDesc::Image { path }) => {
+            let path = base.join(path);
+            let texture = ImageTexture::load(&path)
+                .map_err(|e| format!("could not load texture {}: {}", path.display(), e))?;
+            Ok(Arc::new(texture))
+        }
+        TextureRef::Texture(TextureDesc::Uv) => Ok(Arc::new(UvDebug)),
+        TextureRef::Texture(TextureDesc::Noise {
+            scale,
+            color: c,
+            seed,
+        }) => {
+            check_scale(*scale)?;
+            Ok(Arc::new(NoiseTexture {
+                noise: perlin(*seed),
+                scale: *scale,
+                color: color(name, c)?,
+            }))
+        }
+        TextureRef::Texture(TextureDesc::Marble {
+            scale,
+            color: c,
+            seed,
+        }) => {
+            check_scale(*scale)?;
+            Ok(Arc::new(MarbleTexture {
+                noise: perlin(*seed),
+                scale: *scale,
+                color: color(name, c)?,
+            }))
+        }
+        TextureRef::Texture(TextureDesc::Wood {
+            scale,
+            light,
+            dark,
+            seed,
+        }) => {
+            check_scale(*scale)?;
+            Ok(Arc::new(WoodTexture {
+                noise: perlin(*seed),
+                scale: *scale,
+                light: color(name, light)?,
+                dark: color(name, dark)?,
+            }))
+        }
+    }
+}
+
+fn check_scale(scale: f32) -> Result<(), String> {
+    if !scale.is_finite() || scale <= 0. {
+        return Err(format!("texture scale must be positive, got {}", scale));
+    }
+    Ok(())
+}
+
+fn perlin(seed: u64) -> Arc<Perlin> {
+    Arc::new(Perlin::new(&mut Sampler::new(seed)))
+}
+
+fn build_background(desc: &BackgroundDesc) -> Result<Background, String> {
+    match desc {
+        BackgroundDesc::Color(c) => Ok(Background::Color(color("background", c)?)),
+        BackgroundDesc::Named(name) if name == "sky" => Ok(Background::Sky),
+        BackgroundDesc::Named(name) => Err(format!(
+            "background must be a color or \"sky\", got \"{}\"",
+            name
+        )),
+    }
+}
+
+fn check_radius(radius: f32) -> Result<(), String> {
+    // negative radii are allowed, they flip the normals for hollow glass
+    if radius == 0. || !radius.is_finite() {
+        return Err(format!("radius must be non-zero, got {}", radius));
+    }
+    Ok(())
+}
+
+fn check_size(name: &str, size: f32) -> Result<(), String> {
+    if !size.is_finite() || size <= 0. {
+        return Err(format!("{} must be positive, got {}", name, size));
+    }
+    Ok(())
+}
+
+fn check_direction(name: &str, v: &[f32; 3]) -> Result<(), String> {
+    if vec3(v).length() <= 0. || v.iter().any(|c| !c.is_finite()) {
+        return Err(format!("{} must be a non-zero vector, got {:?}", name, v));
+    }
+    Ok(())
+}
+
+fn color(name: &str, c: &[f32; 3]) -> Result<Color, String> {
+    if c.iter().any(|v| *v < 0.) {
+        return Err(format!("{} must not be negative, got {:?}", name, c));
+    }
+    Ok(vec3(c))
+}
+
+fn vec3(v: &[f32; 3]) -> Point3 {
+    Vec3 {
+        x: v[0],
+        y: v[1],
+        z: v[2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::HitRecord;
+    use crate::ray::Ray;
+
+    const CAMERA: &str = "[camera]\nlookfrom = [0, 0, 5]\nlookat = [0, 0, 0]\n";
+
+    fn error_at(objects: &str) -> (Option<usize>, String) {
+        match parse("test.toml", &format!("{}{}", CAMERA, objects), None) {
+            Ok(_) => panic!("{:?} parsed", objects),
+            Err(e) => (e.line, e.message),
+        }
+    }
+
+    #[test]
+    fn reports_projective_matrices_at_the_matrix() {
+        let (line, message) = error_at(
+            "[[objects]]\n\
+             type = \"union\"\n\
+             material = { type = \"lambertian\", albedo = [0.5, 0.5, 0.5] }\n\
+             [[objects.operands]]\n\
+             type = \"sphere\"\n\
+             center = [0, 0, 0]\n\
+             radius = 1\n\
+             [[objects.operands]]\n\
+             type = \"instance\"\n\
+             object = { type = \"sphere\", center = [0, 0, 0], radius = 1 }\n\
+             matrix = [[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0.5, 0, 1]]\n",
+        );
+        assert_eq!(line, Some(14));
+        assert!(message.contains("must be affine"), "{}", message);
+    }
+
+    #[test]
+    fn objects_start_with_their_type() {
+        let (line, message) =
+            error_at("[[objects]]\ncenter = [0, 0, 0]\ntype = \"sphere\"\nradius = 1\n");
+        assert_eq!(line, Some(4));
+        assert!(
+            message.contains("`type` must be the first key"),
+            "{}",
+            message
+        );
+
+        let (line, message) = error_at(
+            "[[objects]]\ntype = \"sphere\"\ncenter = [0, 0, 0]\nradius = 1\ncolour = 3\n",
+        );
+        assert_eq!(line, Some(8));
+        assert!(message.contains("unknown field `colour`"), "{}", message);
+    }
+
+    #[test]
+    fn reads_affine_instances() {
+        let file = parse(
+            "test.toml",
+            &format!(
+                "{}[[objects]]\n\
+                 type = \"instance\"\n\
+                 matrix = [[1, 0, 0, 2], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]\n\
+                 [objects.object]\n\
+                 type = \"sphere\"\n\
+                 center = [0, 0, 0]\n\
+                 radius = 1\n\
+                 material = {{ type = \"lambertian\", albedo = [0.5, 0.5, 0.5] }}\n",
+                CAMERA
+            ),
+            None,
+        )
+        .unwrap();
+        let mut bounds = Aabb::new(Vec3::new(0.), Vec3::new(0.));
+        assert!(file.scene.world[0].0.bounding_box(0., 1., &mut bounds));
+        assert!((bounds.minimum.x - 1.).abs() < 1e-4 && (bounds.maximum.x - 3.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn builds_unions_over_the_shutter() {
+        let file = parse(
+            "test.toml",
+            &format!(
+                "{}[[objects]]\n\
+                 type = \"union\"\n\
+                 material = {{ type = \"lambertian\", albedo = [0.5, 0.5, 0.5] }}\n\
+                 [[objects.operands]]\n\
+                 type = \"sphere\"\n\
+                 center = [0, -30, 0]\n\
+                 radius = 0.1\n\
+                 [[objects.operands]]\n\
+                 type = \"motion\"\n\
+                 keys = [{{ time = 2, translate = [-10, 0, 0] }}, {{ time = 3, translate = [10, 0, 0] }}]\n\
+                 [objects.operands.object]\n\
+                 type = \"sphere\"\n\
+                 center = [0, 0, 0]\n\
+                 radius = 1\n",
+                CAMERA
+            ),
+            Some((2.4, 2.6)),
+        )
+        .unwrap();
+        let ray = Ray {
+            origin: Point3 {
+                x: 0.,
+                y: 0.,
+                z: 5.,
+            },
+            direction: Vec3 {
+                x: 0.,
+                y: 0.,
+                z: -1.,
+            },
+            time: 2.5,
+        };
+        let mut rec = HitRecord::new();
+        assert!(file.scene.world[0].0.hit(
+            &ray,
+            0.001,
+            f32::INFINITY,
+            &mut rec,
+            &mut Sampler::new(0)
+        ));
+        assert!((rec.t - 4.).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,508 @@
+use crate::camera::Camera;
+use crate::mat4::Mat4;
+use crate::materials::dielectric::Dialectric;
+use crate::materials::diffuse_light::DiffuseLight;
+use crate::materials::isotropic::Isotropic;
+use crate::materials::lambertian::Lambertian;
+use crate::materials::metal::Metal;
+use crate::primitives::cone::Cone;
+use crate::primitives::constant_medium::ConstantMedium;
+use crate::primitives::cuboid::Cuboid;
+use crate::primitives::cylinder::Cylinder;
+use crate::primitives::disk::Disk;
+use crate::primitives::moving_sphere::MovingSphere;
+use crate::primitives::plane::Plane;
+use crate::primitives::quad::Quad;
+use crate::primitives::sdf_object::SdfObject;
+use crate::primitives::sphere::Sphere;
+use crate::primitives::torus::Torus;
+use crate::primitives::transformed::Transformed;
+use crate::sampler::Sampler;
+use crate::scene::{Background, Scene, WorldItem};
+use crate::sdf::mandelbulb::Mandelbulb;
+use crate::textures::checker::CheckerTexture;
+use crate::textures::noise::{MarbleTexture, NoiseTexture, WoodTexture};
+use crate::textures::perlin::Perlin;
+use crate::vec3::{Color, Point3, Vec3, Vec3Attributes};
+use rand::prelude::*;
+use std::sync::Arc;
+
+pub const NAMES: [&str; 6] = [
+    "random-spheres",
+    "simple-light",
+    "cornell-box",
+    "cornell-smoke",
+    "quadrics",
+    "mandelbulb",
+];
+
+pub fn by_name(name: &str, aspect_ratio: f32, seed: u64) -> Option<Scene> {
+    match name {
+        "random-spheres" => Some(random_spheres(aspect_ratio, seed)),
+        "simple-light" => Some(simple_light(aspect_ratio)),
+        "cornell-box" => Some(cornell_box(aspect_ratio)),
+        "cornell-smoke" => Some(cornell_smoke(aspect_ratio)),
+        "quadrics" => Some(quadrics(aspect_ratio)),
+        "mandelbulb" => Some(mandelbulb(aspect_ratio)),
+        _ => None,
+    }
+}
+
+pub fn random_spheres(aspect_ratio: f32, seed: u64) -> Scene {
+    //World
+    let mut world: Vec<WorldItem> = Vec::<WorldItem>::new();
+
+    let ground = Sphere::new(
+        Point3 {
+            x: 0.,
+            y: -1000.,
+            z: 0.,
+        },
+        1000.,
+    );
+
+    // the noise gets its own stream so the sphere layout only depends on `rng`
+    let noise = Arc::new(Perlin::new(&mut Sampler::new(seed ^ 0x006e_6f69_7365)));
+
+    world.push((
+        Arc::new(ground),
+        Arc::new(Lambertian::textured(Arc::new(NoiseTexture {
+            noise: noise.clone(),
+            scale: 1.,
+            color: Color::new(0.5),
+        }))),
+    ));
+
+    let mut rng = Sampler::new(seed);
+    for i in -11..11 {
+        for j in -11..11 {
+            let mat = rng.gen_range(0. ..1.);
+            let center = Point3 {
+                x: (i as f32) + 0.9 * rng.gen_range(0. ..1.),
+                y: 0.2,
+                z: (j as f32) + 0.9 * rng.gen_range(0. ..1.),
+            };
+
+            if (center
+                - Point3 {
+                    x: 4.,
+                    y: 0.2,
+                    z: 0.,
+                })
+            .length()
+                > 0.9
+            {
+                if mat < 0.8 {
+                    let center2 = center
+                        + Vec3 {
+                            x: 0.,
+                            y: rng.gen_range(0. ..0.5),
+                            z: 0.,
+                        };
+
+                    let object = MovingSphere::new(center, center2, 0., 1., 0.2);
+
+                    world.push((
+                        Arc::new(object),
+                        Arc::new(Lambertian::textured(Arc::new(MarbleTexture {
+                            noise: noise.clone(),
+                            scale: 4.,
+                            color: Color::random(&mut rng) * Color::random(&mut rng),
+                        }))),
+                    ));
+                } else if mat < 0.95 {
+                    let object = Sphere::new(center, 0.2);
+
+                    world.push((
+                        Arc::new(object),
+                        Arc::new(Metal::new(
+                            Color::random(&mut rng) * Color::random(&mut rng),
+                            rng.gen_range(0. ..0.5),
+                        )),
+                    ));
+                } else {
+                    let object = Sphere::new(center, 0.2);
+                    world.push((Arc::new(object), Arc::new(Dialectric { ir: 1.5 })));
+                }
+            }
+        }
+    }
+
+    let one = Sphere::new(
+        Point3 {
+            x: -4.,
+            y: 1.,
+            z: 0.,
+        },
+        1.,
+    );
+
+    world.push((
+        Arc::new(one),
+        Arc::new(Lambertian::textured(Arc::new(WoodTexture {
+            noise,
+            scale: 3.,
+            light: Color {
+                x: 0.5,
+                y: 0.3,
+                z: 0.15,
+            },
+            dark: Color {
+                x: 0.25,
+                y: 0.12,
+                z: 0.05,
+            },
+        }))),
+    ));
+
+    let two = Sphere::new(
+        Point3 {
+            x: 0.,
+            y: 1.,
+            z: 0.,
+        },
+        1.,
+    );
+    world.push((Arc::new(two), Arc::new(Dialectric { ir: 1.5 })));
+
+    let three = Sphere::new(
+        Point3 {
+            x: 4.,
+            y: 1.,
+            z: 0.,
+        },
+        1.,
+    );
+
+    world.push((
+        Arc::new(three),
+        Arc::new(Metal::new(
+            Color {
+                x: 0.7,
+                y: 0.6,
+                z: 0.5,
+            },
+            0.,
+        )),
+    ));
+
+    let lookfrom = Vec3 {
+        x: 13.,
+        y: 2.,
+        z: 3.,
+    };
+
+    let lookat = Vec3 {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+    };
+    let camera: Camera = Camera::new(
+        lookfrom,
+        lookat,
+        Vec3 {
+            x: 0.,
+            y: 1.,
+            z: 0.,
+        },
+        20.,
+        aspect_ratio,
+        0.1,
+        10.,
+    );
+
+    Scene {
+        world,
+        camera,
+        background: Background::Sky,
+    }
+}
+
+// Two spheres on a ground plane lit only by an emissive sphere above them,
+// against a black background.
+pub fn simple_light(aspect_ratio: f32) -> Scene {
+    let camera = Camera::new(
+        Point3 {
+            x: 26.,
+            y: 3.,
+            z: 6.,
+        },
+        Point3 {
+            x: 0.,
+            y: 2.,
+            z: 0.,
+        },
+        Vec3 {
+            x: 0.,
+            y: 1.,
+            z: 0.,
+        },
+        20.,
+        aspect_ratio,
+        0.,
+        10.,
+    );
+    let mut scene = Scene::new(camera);
+    scene.background = Background::Color(Color::new(0.));
+
+    let grey = Arc::new(Lambertian::new(Color::new(0.5)));
+    scene.add(
+        Arc::new(Sphere::new(
+            Point3 {
+                x: 0.,
+                y: -1000.,
+                z: 0.,
+            },
+            1000.,
+        )),
+        grey.clone(),
+    );
+    scene.add(
+        Arc::new(Sphere::new(
+            Point3 {
+                x: 0.,
+                y: 2.,
+                z: 0.,
+            },
+            2.,
+        )),
+        grey,
+    );
+    scene.add(
+        Arc::new(Sphere::new(
+            Point3 {
+                x: 0.,
+                y: 7.,
+                z: 0.,
+            },
+            2.,
+        )),
+        Arc::new(DiffuseLight::new(Color::new(4.))),
+    );
+
+    scene
+}
+
+// The classic 555 unit Cornell box with two white blocks, lit only by the
+// ceiling light. Looks best square, with -a 1.
+pub fn cornell_box(aspect_ratio: f32) -> Scene {
+    let (mut scene, white) = cornell_room(aspect_ratio);
+    let (tall, short) = cornell_blocks();
+    scene.add(Arc::new(tall), white.clone());
+    scene.add(Arc::new(short), white);
+    scene
+}
+
+// The same room with the two blocks made of smoke, one dark and one light.
+pub fn cornell_smoke(aspect_ratio: f32) -> Scene {
+    let (mut scene, _) = cornell_room(aspect_ratio);
+    let (tall, short) = cornell_blocks();
+    scene.add(
+        Arc::new(ConstantMedium::new(tall, 0.01)),
+        Arc::new(Isotropic::new(Color::new(0.))),
+    );
+    scene.add(
+        Arc::new(ConstantMedium::new(short, 0.01)),
+        Arc::new(Isotropic::new(Color::new(1.))),
+    );
+    scene
+}
+
+// Walls and light of the Cornell box, returned with its white material.
+fn cornell_room(aspect_ratio: f32) -> (Scene, Arc<Lambertian>) {
+    let camera = Camera::new(
+        Point3 {
+            x: 278.,
+            y: 278.,
+            z: -800.,
+        },
+        Point3 {
+            x: 278.,
+            y: 278.,
+            z: 0.,
+        },
+        Vec3 {
+            x: 0.,
+            y: 1.,
+            z: 0.,
+        },
+        40.,
+        aspect_ratio,
+        0.,
+        10.,
+    );
+    let mut scene = Scene::new(camera);
+    scene.background = Background::Color(Color::new(0.));
+
+    let red = Arc::new(Lambertian::new(Color {
+        x: 0.65,
+        y: 0.05,
+        z: 0.05,
+    }));
+    let white = Arc::new(Lambertian::new(Color {
+        x: 0.73,
+        y: 0.73,
+        z: 0.73,
+    }));
+    let green = Arc::new(Lambertian::new(Color {
+        x: 0.12,
+        y: 0.45,
+        z: 0.15,
+    }));
+    let light = Arc::new(DiffuseLight::new(Color::new(15.)));
+
+    let point = |x, y, z| Point3 { x, y, z };
+
+    scene.add(
+        Arc::new(Quad::new(
+            point(555., 0., 0.),
+            point(0., 555., 0.),
+            point(0., 0., 555.),
+        )),
+        green,
+    );
+    scene.add(
+        Arc::new(Quad::new(
+            point(0., 0., 0.),
+            point(0., 555., 0.),
+            point(0., 0., 555.),
+        )),
+        red,
+    );
+    scene.add(
+        Arc::new(Quad::new(
+            point(343., 554., 332.),
+            point(-130., 0., 0.),
+            point(0., 0., -105.),
+        )),
+        light,
+    );
+    scene.add(
+        Arc::new(Quad::new(
+            point(0., 0., 0.),
+            point(555., 0., 0.),
+            point(0., 0., 555.),
+        )),
+        white.clone(),
+    );
+    scene.add(
+        Arc::new(Quad::new(
+            point(555., 555., 555.),
+            point(-555., 0., 0.),
+            point(0., 0., -555.),
+        )),
+        white.clone(),
+    );
+    scene.add(
+        Arc::new(Quad::new(
+            point(0., 0., 555.),
+            point(555., 0., 0.),
+            point(0., 555., 0.),
+        )),
+        white.clone(),
+    );
+
+    (scene, white)
+}
+
+fn cornell_blocks() -> (Transformed<Cuboid>, Transformed<Cuboid>) {
+    let point = |x, y, z| Point3 { x, y, z };
+    let y_axis = point(0., 1., 0.);
+    let tall = Transformed::new(
+        Cuboid::new(point(0., 0., 0.), point(165., 330., 165.)),
+        Mat4::translation(point(265., 0., 295.)) * Mat4::rotation(y_axis, 15.),
+    )
+    .unwrap();
+    let short = Transformed::new(
+        Cuboid::new(point(0., 0., 0.), point(165., 165., 165.)),
+        Mat4::translation(point(130., 0., 65.)) * Mat4::rotation(y_axis, -18.),
+    )
+    .unwrap();
+    (tall, short)
+}
+
+// One of each analytic shape on an infinite checkered floor.
+pub fn quadrics(aspect_ratio: f32) -> Scene {
+    let point = |x, y, z| Point3 { x, y, z };
+    let camera = Camera::new(
+        point(0., 4., 10.),
+        point(0., 1., 0.),
+        point(0., 1., 0.),
+        35.,
+        aspect_ratio,
+        0.,
+        10.,
+    );
+    let mut scene = Scene::new(camera);
+
+    let floor = Arc::new(Lambertian::textured(Arc::new(CheckerTexture::from_colors(
+        1.,
+        Color::new(0.2),
+        Color::new(0.8),
+    ))));
+    let red = Arc::new(Lambertian::new(point(0.7, 0.15, 0.1)));
+    let blue = Arc::new(Lambertian::new(point(0.1, 0.2, 0.6)));
+    let brass = Arc::new(Metal::new(point(0.8, 0.6, 0.3), 0.2));
+    let glass = Arc::new(Dialectric { ir: 1.5 });
+    let up = point(0., 1., 0.);
+
+    scene.add(Arc::new(Plane::new(point(0., 0., 0.), up)), floor);
+    scene.add(
+        Arc::new(Cylinder::new(
+            point(-3., 0., 0.),
+            point(-3., 2., 0.),
+            0.7,
+            true,
+        )),
+        brass.clone(),
+    );
+    scene.add(
+        Arc::new(Cone::new(
+            point(-1., 0., -1.),
+            point(-1., 2.5, -1.),
+            0.8,
+            true,
+        )),
+        red,
+    );
+    scene.add(
+        Arc::new(Torus::new(point(1.2, 1.2, 0.), point(0., 1., 1.), 0.9, 0.3)),
+        glass,
+    );
+    scene.add(
+        Arc::new(Torus::new(point(3.4, 0.25, 0.5), up, 0.8, 0.25)),
+        brass,
+    );
+    scene.add(Arc::new(Disk::new(point(0., 0.01, 2.5), up, 0.9)), blue);
+
+    scene
+}
+
+// The power 8 Mandelbulb in brushed gold, resting on a dark floor.
+pub fn mandelbulb(aspect_ratio: f32) -> Scene {
+    let point = |x, y, z| Point3 { x, y, z };
+    let camera = Camera::new(
+        point(2.2, 1.6, 2.6),
+        point(0., 0.05, 0.),
+        point(0., 1., 0.),
+        40.,
+        aspect_ratio,
+        0.,
+        10.,
+    );
+    let mut scene = Scene::new(camera);
+
+    scene.add(
+        Arc::new(Plane::new(point(0., -1.2, 0.), point(0., 1., 0.))),
+        Arc::new(Lambertian::new(Color::new(0.15))),
+    );
+    scene.add(
+        Arc::new(SdfObject::new(Arc::new(Mandelbulb {
+            power: 8.,
+            iterations: 12,
+        }))),
+        Arc::new(Metal::new(point(0.9, 0.7, 0.35), 0.3)),
+    );
+
+    scene
+}
@@ -0,0 +1,13 @@
+use crate::aabb::Aabb;
+use crate::vec3::Point3;
+
+// A signed distance field: how far `p` is from the nearest surface, negative
+// inside. It may underestimate but never overestimate, or sphere tracing
+// steps through thin parts.
+pub trait Distance {
+    fn distance(&self, p: &Point3) -> f32;
+
+    // Where the surface can be, None for fields that go on forever such as
+    // unlimited repetitions.
+    fn bounds(&self) -> Option<Aabb>;
+}
@@ -0,0 +1,45 @@
+use crate::aabb::Aabb;
+use crate::sdf::distance::Distance;
+use crate::vec3::{Point3, Vec3, Vec3Attributes};
+
+// The Mandelbulb fractal around the origin with its poles on the y axis,
+// about 1.2 units in radius for the usual power of 8 and always within the
+// escape radius of 2. The distance is the standard estimate from the running
+// derivative; more `iterations` give finer detail.
+#[derive(Copy, Clone)]
+pub struct Mandelbulb {
+    pub power: f32,
+    pub iterations: u32,
+}
+
+impl Distance for Mandelbulb {
+    fn distance(&self, p: &Point3) -> f32 {
+        let mut z = *p;
+        let mut dr = 1.;
+        let mut r = z.length();
+        for _ in 0..self.iterations {
+            if r > 2. {
+                break;
+            }
+            // raise z to the power in spherical coordinates
+            let theta = (z.y / r).clamp(-1., 1.).acos() * self.power;
+            let phi = z.z.atan2(z.x) * self.power;
+            dr = r.powf(self.power - 1.) * self.power * dr + 1.;
+            let zr = r.powf(self.power);
+            z = Vec3 {
+                x: zr * theta.sin() * phi.cos(),
+                y: zr * theta.cos(),
+                z: zr * theta.sin() * phi.sin(),
+            } + *p;
+            r = z.length();
+        }
+        if r <= 0. {
+            return 0.;
+        }
+        0.5 * r.ln() * r / dr
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Aabb::new(Vec3::new(-2.), Vec3::new(2.)))
+    }
+}
@@ -0,0 +1,4 @@
+pub mod distance;
+pub mod mandelbulb;
+pub mod operators;
+pub mod shapes;
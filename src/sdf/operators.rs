@@ -0,0 +1,94 @@
+use crate::aabb::Aabb;
+use crate::sdf::distance::Distance;
+use crate::vec3::{Point3, Vec3};
+use std::sync::Arc;
+
+// Union of `a` and `b` blended over a distance of about `k` where they meet,
+// with the polynomial smooth minimum.
+#[derive(Clone)]
+pub struct SmoothUnion {
+    pub a: Arc<dyn Distance + Sync + Send>,
+    pub b: Arc<dyn Distance + Sync + Send>,
+    pub k: f32,
+}
+
+impl Distance for SmoothUnion {
+    fn distance(&self, p: &Point3) -> f32 {
+        let (a, b) = (self.a.distance(p), self.b.distance(p));
+        let h = (0.5 + 0.5 * (b - a) / self.k).clamp(0., 1.);
+        b + (a - b) * h - self.k * h * (1. - h)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        // the blend bulges out by at most k / 4
+        let a = self.a.bounds()?;
+        let b = self.b.bounds()?;
+        let grow = Vec3::new(0.25 * self.k);
+        let both = Aabb::surrounding_box(a, b);
+        Some(Aabb::new(both.minimum - grow, both.maximum + grow))
+    }
+}
+
+// `b` carved out of `a`, with the cut edges rounded over about `k`.
+#[derive(Clone)]
+pub struct SmoothSubtraction {
+    pub a: Arc<dyn Distance + Sync + Send>,
+    pub b: Arc<dyn Distance + Sync + Send>,
+    pub k: f32,
+}
+
+impl Distance for SmoothSubtraction {
+    fn distance(&self, p: &Point3) -> f32 {
+        let (a, b) = (self.a.distance(p), self.b.distance(p));
+        let h = (0.5 - 0.5 * (a + b) / self.k).clamp(0., 1.);
+        a + (-b - a) * h + self.k * h * (1. - h)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.a.bounds()
+    }
+}
+
+// Copies of `shape` every `period` along each axis, an axis with a period of
+// zero is not repeated. `copies` limits them to that many either side of
+// the original per axis; without it they fill all of space. The shape should
+// fit within one period around the origin.
+#[derive(Clone)]
+pub struct Repeat {
+    pub shape: Arc<dyn Distance + Sync + Send>,
+    pub period: Vec3,
+    pub copies: Option<[u32; 3]>,
+}
+
+impl Distance for Repeat {
+    fn distance(&self, p: &Point3) -> f32 {
+        let fold = |x: f32, period: f32, copies: Option<u32>| {
+            if period <= 0. {
+                return x;
+            }
+            let mut cell = (x / period).round();
+            if let Some(n) = copies {
+                cell = cell.clamp(-(n as f32), n as f32);
+            }
+            x - period * cell
+        };
+        let copies = |axis: usize| self.copies.map(|c| c[axis]);
+        let q = Point3 {
+            x: fold(p.x, self.period.x, copies(0)),
+            y: fold(p.y, self.period.y, copies(1)),
+            z: fold(p.z, self.period.z, copies(2)),
+        };
+        self.shape.distance(&q)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let shape = self.shape.bounds()?;
+        let copies = self.copies?;
+        let reach = Vec3 {
+            x: self.period.x * copies[0] as f32,
+            y: self.period.y * copies[1] as f32,
+            z: self.period.z * copies[2] as f32,
+        };
+        Some(Aabb::new(shape.minimum - reach, shape.maximum + reach))
+    }
+}
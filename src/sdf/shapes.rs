@@ -0,0 +1,104 @@
+use crate::aabb::Aabb;
+use crate::sdf::distance::Distance;
+use crate::vec3::{Math, Point3, Vec3, Vec3Attributes};
+
+#[derive(Copy, Clone)]
+pub struct Sphere {
+    pub center: Point3,
+    pub radius: f32,
+}
+
+impl Distance for Sphere {
+    fn distance(&self, p: &Point3) -> f32 {
+        (*p - self.center).length() - self.radius
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+// An axis aligned box reaching `half_size` from its center, with its edges
+// rounded off by `radius`; zero gives a sharp box.
+#[derive(Copy, Clone)]
+pub struct RoundedBox {
+    pub center: Point3,
+    pub half_size: Vec3,
+    pub radius: f32,
+}
+
+impl Distance for RoundedBox {
+    fn distance(&self, p: &Point3) -> f32 {
+        let d = *p - self.center;
+        let q = Vec3 {
+            x: d.x.abs() - self.half_size.x + self.radius,
+            y: d.y.abs() - self.half_size.y + self.radius,
+            z: d.z.abs() - self.half_size.z + self.radius,
+        };
+        let outside = Vec3 {
+            x: q.x.max(0.),
+            y: q.y.max(0.),
+            z: q.z.max(0.),
+        };
+        outside.length() + q.x.max(q.y).max(q.z).min(0.) - self.radius
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Aabb::new(
+            self.center - self.half_size,
+            self.center + self.half_size,
+        ))
+    }
+}
+
+// A ring around the y axis, see `primitives::torus` for the analytic one.
+#[derive(Copy, Clone)]
+pub struct Torus {
+    pub center: Point3,
+    pub major: f32,
+    pub minor: f32,
+}
+
+impl Distance for Torus {
+    fn distance(&self, p: &Point3) -> f32 {
+        let d = *p - self.center;
+        let ring = (d.x * d.x + d.z * d.z).sqrt() - self.major;
+        (ring * ring + d.y * d.y).sqrt() - self.minor
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let outer = self.major + self.minor;
+        let extent = Vec3 {
+            x: outer,
+            y: self.minor,
+            z: outer,
+        };
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+// A cylinder from `a` to `b` with hemispherical ends.
+#[derive(Copy, Clone)]
+pub struct Capsule {
+    pub a: Point3,
+    pub b: Point3,
+    pub radius: f32,
+}
+
+impl Distance for Capsule {
+    fn distance(&self, p: &Point3) -> f32 {
+        let pa = *p - self.a;
+        let ba = self.b - self.a;
+        let h = (pa.dot(ba) / ba.dot(ba)).clamp(0., 1.);
+        (pa - Vec3::new(h) * ba).length() - self.radius
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius);
+        Some(Aabb::surrounding_box(
+            Aabb::new(self.a - extent, self.a + extent),
+            Aabb::new(self.b - extent, self.b + extent),
+        ))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vec3::{Color, Point3};
+use std::sync::Arc;
+
+// A solid checkerboard of cubes with edge length `scale`, evaluated at the
+// hit point so it needs no surface parameterization.
+#[derive(Clone)]
+pub struct CheckerTexture {
+    pub scale: f32,
+    pub even: Arc<dyn Texture + Sync + Send>,
+    pub odd: Arc<dyn Texture + Sync + Send>,
+}
+
+impl CheckerTexture {
+    pub fn new(
+        scale: f32,
+        even: Arc<dyn Texture + Sync + Send>,
+        odd: Arc<dyn Texture + Sync + Send>,
+    ) -> Self {
+        Self { scale, even, odd }
+    }
+
+    pub fn from_colors(scale: f32, even: Color, odd: Color) -> Self {
+        Self::new(
+            scale,
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+        )
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color {
+        let x = (p.x / self.scale).floor() as i64;
+        let y = (p.y / self.scale).floor() as i64;
+        let z = (p.z / self.scale).floor() as i64;
+
+        if (x + y + z) % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
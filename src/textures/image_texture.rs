@@ -0,0 +1,79 @@
+use crate::textures::texture::Texture;
+use crate::tonemap::srgb_decode;
+use crate::vec3::{Color, Point3, Vec3};
+use image::{DynamicImage, ImageError};
+use std::path::Path;
+use std::sync::Arc;
+
+// An image mapped over (u, v) with v pointing up. Texels are decoded from
+// sRGB once at load time and filtered bilinearly; u wraps around, v is
+// clamped at the poles.
+#[derive(Clone)]
+pub struct ImageTexture {
+    width: u32,
+    height: u32,
+    texels: Arc<Vec<Color>>,
+}
+
+impl ImageTexture {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
+        Ok(Self::from_image(image::open(path)?))
+    }
+
+    pub fn from_memory(bytes: &[u8]) -> Result<Self, ImageError> {
+        Ok(Self::from_image(image::load_from_memory(bytes)?))
+    }
+
+    pub fn from_image(image: DynamicImage) -> Self {
+        let image = image.to_rgb8();
+        let texels = image
+            .pixels()
+            .map(|p| Color {
+                x: srgb_decode(p[0] as f32 / 255.),
+                y: srgb_decode(p[1] as f32 / 255.),
+                z: srgb_decode(p[2] as f32 / 255.),
+            })
+            .collect();
+
+        Self {
+            width: image.width(),
+            height: image.height(),
+            texels: Arc::new(texels),
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let x = x.rem_euclid(self.width as i64) as u32;
+        let y = y.clamp(0, self.height as i64 - 1) as u32;
+        self.texels[(y * self.width + x) as usize]
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _p: &Point3) -> Color {
+        if self.width == 0 || self.height == 0 {
+            return Color {
+                x: 0.,
+                y: 1.,
+                z: 1.,
+            };
+        }
+
+        // texel centers sit at half integer coordinates
+        let x = u * self.width as f32 - 0.5;
+        let y = (1. - v.clamp(0., 1.)) * self.height as f32 - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let fx = x - x0;
+        let fy = y - y0;
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), fx);
+        let bottom = lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), fx);
+        lerp(top, bottom, fy)
+    }
+}
+
+fn lerp(a: Color, b: Color, t: f32) -> Color {
+    Vec3::new(1. - t) * a + Vec3::new(t) * b
+}
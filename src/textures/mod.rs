@@ -0,0 +1,8 @@
+pub mod checker;
+pub mod image_texture;
+pub mod noise;
+pub mod perlin;
+pub mod solid_color;
+pub mod texture;
+pub mod uv_debug;
+pub mod vertex_colors;
@@ -0,0 +1,57 @@
+use crate::textures::perlin::Perlin;
+use crate::textures::texture::Texture;
+use crate::vec3::{Color, Point3, Vec3};
+use std::sync::Arc;
+
+const TURBULENCE_DEPTH: u32 = 7;
+
+// Fractal turbulence blended over `color`, a cloudy or stony look.
+#[derive(Clone)]
+pub struct NoiseTexture {
+    pub noise: Arc<Perlin>,
+    pub scale: f32,
+    pub color: Color,
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f32, _v: f32, p: &Point3) -> Color {
+        let p = Vec3::new(self.scale) * *p;
+        let turbulence = self.noise.turbulence(&p, TURBULENCE_DEPTH).min(1.);
+        Vec3::new(0.5 * (1. + turbulence)) * self.color
+    }
+}
+
+// Veins running along z, bent by turbulence.
+#[derive(Clone)]
+pub struct MarbleTexture {
+    pub noise: Arc<Perlin>,
+    pub scale: f32,
+    pub color: Color,
+}
+
+impl Texture for MarbleTexture {
+    fn value(&self, _u: f32, _v: f32, p: &Point3) -> Color {
+        let p = Vec3::new(self.scale) * *p;
+        let phase = p.z + 10. * self.noise.turbulence(&p, TURBULENCE_DEPTH);
+        Vec3::new(0.5 * (1. + phase.sin())) * self.color
+    }
+}
+
+// Growth rings around the y axis, blending from `light` to `dark` across
+// each ring, with turbulence to keep them from looking machined.
+#[derive(Clone)]
+pub struct WoodTexture {
+    pub noise: Arc<Perlin>,
+    pub scale: f32,
+    pub light: Color,
+    pub dark: Color,
+}
+
+impl Texture for WoodTexture {
+    fn value(&self, _u: f32, _v: f32, p: &Point3) -> Color {
+        let p = Vec3::new(self.scale) * *p;
+        let rings = (p.x * p.x + p.z * p.z).sqrt() + 2. * self.noise.turbulence(&p, 4);
+        let t = rings - rings.floor();
+        Vec3::new(1. - t) * self.light + Vec3::new(t) * self.dark
+    }
+}
@@ -0,0 +1,107 @@
+use crate::sampler::Sampler;
+use crate::vec3::{Math, Point3, Vec3};
+use rand::seq::SliceRandom;
+
+const POINT_COUNT: usize = 256;
+
+// Gradient noise over a 256 cell lattice that repeats in every direction.
+// The gradients and permutations come from the sampler, so the same seed
+// always gives the same pattern.
+#[derive(Clone)]
+pub struct Perlin {
+    gradients: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(sampler: &mut Sampler) -> Self {
+        let gradients = (0..POINT_COUNT)
+            .map(|_| Vec3::random_rng(sampler, -1., 1.).unit())
+            .collect();
+
+        Self {
+            gradients,
+            perm_x: permutation(sampler),
+            perm_y: permutation(sampler),
+            perm_z: permutation(sampler),
+        }
+    }
+
+    // Roughly in [-1, 1].
+    pub fn noise(&self, p: &Point3) -> f32 {
+        let u = p.x - p.x.floor();
+        let v = p.y - p.y.floor();
+        let w = p.z - p.z.floor();
+        let i = p.x.floor() as i64;
+        let j = p.y.floor() as i64;
+        let k = p.z.floor() as i64;
+
+        let mut c = [[[Vec3::new(0.); 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, gradient) in row.iter_mut().enumerate() {
+                    let index = self.perm_x[wrap(i + di as i64)]
+                        ^ self.perm_y[wrap(j + dj as i64)]
+                        ^ self.perm_z[wrap(k + dk as i64)];
+                    *gradient = self.gradients[index];
+                }
+            }
+        }
+
+        interpolate(&c, u, v, w)
+    }
+
+    // Sum of `depth` octaves, each at twice the frequency and half the weight
+    // of the one before.
+    pub fn turbulence(&self, p: &Point3, depth: u32) -> f32 {
+        let mut accum = 0.;
+        let mut p = *p;
+        let mut weight = 1.;
+
+        for _ in 0..depth {
+            accum += weight * self.noise(&p);
+            weight *= 0.5;
+            p = Vec3::new(2.) * p;
+        }
+
+        accum.abs()
+    }
+}
+
+fn permutation(sampler: &mut Sampler) -> Vec<usize> {
+    let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+    p.shuffle(sampler);
+    p
+}
+
+fn wrap(i: i64) -> usize {
+    (i & (POINT_COUNT as i64 - 1)) as usize
+}
+
+fn interpolate(c: &[[[Vec3; 2]; 2]; 2], u: f32, v: f32, w: f32) -> f32 {
+    // Hermite smoothing hides the lattice
+    let uu = u * u * (3. - 2. * u);
+    let vv = v * v * (3. - 2. * v);
+    let ww = w * w * (3. - 2. * w);
+
+    let mut accum = 0.;
+    for (i, plane) in c.iter().enumerate() {
+        for (j, row) in plane.iter().enumerate() {
+            for (k, gradient) in row.iter().enumerate() {
+                let (fi, fj, fk) = (i as f32, j as f32, k as f32);
+                let weight = Vec3 {
+                    x: u - fi,
+                    y: v - fj,
+                    z: w - fk,
+                };
+                accum += (fi * uu + (1. - fi) * (1. - uu))
+                    * (fj * vv + (1. - fj) * (1. - vv))
+                    * (fk * ww + (1. - fk) * (1. - ww))
+                    * gradient.dot(weight);
+            }
+        }
+    }
+    accum
+}
@@ -0,0 +1,19 @@
+use crate::textures::texture::Texture;
+use crate::vec3::{Color, Point3};
+
+#[derive(Copy, Clone)]
+pub struct SolidColor {
+    pub color: Color,
+}
+
+impl SolidColor {
+    pub fn new(color: Color) -> Self {
+        Self { color }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f32, _v: f32, _p: &Point3) -> Color {
+        self.color
+    }
+}
@@ -0,0 +1,31 @@
+use crate::hittable::HitRecord;
+use crate::vec3::{Color, Point3};
+
+pub trait Texture: TexClone {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color;
+
+    // The color at a surface hit. Textures that need more of the hit than
+    // its coordinates, such as vertex colors, override this.
+    fn value_at(&self, rec: &HitRecord) -> Color {
+        self.value(rec.u, rec.v, &rec.p)
+    }
+}
+
+pub trait TexClone {
+    fn clone_box(&self) -> Box<dyn Texture>;
+}
+
+impl<T> TexClone for T
+where
+    T: 'static + Texture + Clone,
+{
+    fn clone_box(&self) -> Box<dyn Texture> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Texture> {
+    fn clone(&self) -> Box<dyn Texture> {
+        self.clone_box()
+    }
+}
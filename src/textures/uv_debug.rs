@@ -0,0 +1,12 @@
+use crate::textures::texture::Texture;
+use crate::vec3::{Color, Point3};
+
+// Shows the surface parameterization: u in red, v in green.
+#[derive(Copy, Clone)]
+pub struct UvDebug;
+
+impl Texture for UvDebug {
+    fn value(&self, u: f32, v: f32, _p: &Point3) -> Color {
+        Color { x: u, y: v, z: 0. }
+    }
+}
@@ -0,0 +1,18 @@
+use crate::hittable::HitRecord;
+use crate::textures::texture::Texture;
+use crate::vec3::{Color, Point3};
+
+// The vertex colors of a mesh, blended across each triangle by the mesh
+// itself and handed over in the hit record. Black on anything without them.
+#[derive(Copy, Clone)]
+pub struct VertexColors;
+
+impl Texture for VertexColors {
+    fn value(&self, _u: f32, _v: f32, _p: &Point3) -> Color {
+        Color::new(0.)
+    }
+
+    fn value_at(&self, rec: &HitRecord) -> Color {
+        rec.color.unwrap_or_else(|| Color::new(0.))
+    }
+}
@@ -0,0 +1,165 @@
+use crate::vec3::{Color, Math, Rgb, Vec3};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Operator {
+    Clamp,
+    Reinhard,
+    ExtendedReinhard,
+    Aces,
+    Uncharted2,
+}
+
+pub const OPERATOR_NAMES: [&str; 5] = [
+    "clamp",
+    "reinhard",
+    "extended-reinhard",
+    "aces",
+    "uncharted2",
+];
+
+impl FromStr for Operator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clamp" => Ok(Operator::Clamp),
+            "reinhard" => Ok(Operator::Reinhard),
+            "extended-reinhard" => Ok(Operator::ExtendedReinhard),
+            "aces" => Ok(Operator::Aces),
+            "uncharted2" => Ok(Operator::Uncharted2),
+            _ => Err(format!(
+                "unknown tone mapping operator {}, expected one of: {}",
+                s,
+                OPERATOR_NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Operator::Clamp => OPERATOR_NAMES[0],
+            Operator::Reinhard => OPERATOR_NAMES[1],
+            Operator::ExtendedReinhard => OPERATOR_NAMES[2],
+            Operator::Aces => OPERATOR_NAMES[3],
+            Operator::Uncharted2 => OPERATOR_NAMES[4],
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Turns linear radiance into display values: scale by the exposure (in stops),
+// compress with the operator, then encode with the sRGB transfer function.
+// `white` is the smallest luminance that maps to pure white for the extended
+// Reinhard operator.
+#[derive(Copy, Clone, Debug)]
+pub struct ToneMapping {
+    pub operator: Operator,
+    pub exposure: f32,
+    pub white: f32,
+}
+
+impl Default for ToneMapping {
+    fn default() -> Self {
+        Self {
+            operator: Operator::Clamp,
+            exposure: 0.,
+            white: 4.,
+        }
+    }
+}
+
+impl ToneMapping {
+    pub fn map(&self, c: Color) -> Color {
+        let c = Vec3::new(2f32.powf(self.exposure)) * c;
+        match self.operator {
+            Operator::Clamp => c,
+            Operator::Reinhard => scale_luminance(c, |l| l / (1. + l)),
+            Operator::ExtendedReinhard => {
+                let white_squared = self.white * self.white;
+                scale_luminance(c, |l| l * (1. + l / white_squared) / (1. + l))
+            }
+            Operator::Aces => per_channel(c, aces),
+            Operator::Uncharted2 => {
+                let white_scale = 1. / uncharted2(11.2);
+                per_channel(c, |x| uncharted2(2. * x.max(0.)) * white_scale)
+            }
+        }
+    }
+
+    pub fn to_rgb(&self, c: Color) -> Rgb {
+        let c = self.map(c);
+        Rgb {
+            r: quantize(srgb_encode(c.x)),
+            g: quantize(srgb_encode(c.y)),
+            b: quantize(srgb_encode(c.z)),
+        }
+    }
+}
+
+pub fn luminance(c: Color) -> f32 {
+    c.dot(Vec3 {
+        x: 0.2126,
+        y: 0.7152,
+        z: 0.0722,
+    })
+}
+
+pub fn srgb_encode(linear: f32) -> f32 {
+    if linear <= 0.003_130_8 {
+        12.92 * linear
+    } else {
+        1.055 * linear.powf(1. / 2.4) - 0.055
+    }
+}
+
+pub fn srgb_decode(encoded: f32) -> f32 {
+    if encoded <= 0.040_45 {
+        encoded / 12.92
+    } else {
+        ((encoded + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn quantize(v: f32) -> u8 {
+    // NaN falls through the clamp as 0
+    (v.clamp(0., 1.) * 255. + 0.5) as u8
+}
+
+fn scale_luminance<F: Fn(f32) -> f32>(c: Color, curve: F) -> Color {
+    let l = luminance(c);
+    if l <= 0. {
+        return Color::new(0.);
+    }
+    Vec3::new(curve(l) / l) * c
+}
+
+fn per_channel<F: Fn(f32) -> f32>(c: Color, curve: F) -> Color {
+    Color {
+        x: curve(c.x),
+        y: curve(c.y),
+        z: curve(c.z),
+    }
+}
+
+// Krzysztof Narkowicz's fit of the ACES filmic curve.
+fn aces(x: f32) -> f32 {
+    let x = x.max(0.);
+    (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)
+}
+
+// John Hable's filmic curve from Uncharted 2.
+fn uncharted2(x: f32) -> f32 {
+    let a = 0.15;
+    let b = 0.50;
+    let c = 0.10;
+    let d = 0.20;
+    let e = 0.02;
+    let f = 0.30;
+    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
@@ -1,3 +1,4 @@
+use crate::sampler::Sampler;
 use core::ops::Neg;
 use rand::prelude::*;
 use std::ops::Add;
@@ -15,7 +16,8 @@ pub struct Vec3 {
     pub z: f32,
 }
 
-pub struct RBG {
+#[derive(Copy, Clone, Debug)]
+pub struct Rgb {
     pub r: u8,
     pub g: u8,
     pub b: u8,
@@ -32,16 +34,14 @@ impl Vec3 {
     pub fn new(t: f32) -> Vec3 {
         Vec3 { x: t, y: t, z: t }
     }
-    pub fn random() -> Vec3 {
-        let mut rng = rand::thread_rng();
+    pub fn random(rng: &mut Sampler) -> Vec3 {
         Vec3 {
             x: rng.gen_range(0. ..1.),
             y: rng.gen_range(0. ..1.),
             z: rng.gen_range(0. ..1.),
         }
     }
-    pub fn random_rng(min: f32, max: f32) -> Vec3 {
-        let mut rng = rand::thread_rng();
+    pub fn random_rng(rng: &mut Sampler, min: f32, max: f32) -> Vec3 {
         Vec3 {
             x: rng.gen_range(min..max),
             y: rng.gen_range(min..max),
@@ -62,7 +62,7 @@ impl Vec3Attributes for Vec3 {
         (self.x.abs() < s) && (self.y.abs() < s) && (self.z.abs() < s)
     }
     fn reflect(self, other: Vec3) -> Vec3 {
-        return self - Vec3::new(2.) * Vec3::new(self.dot(other)) * other;
+        self - Vec3::new(2.) * Vec3::new(self.dot(other)) * other
     }
 }
 
@@ -151,20 +151,6 @@ pub trait Math {
     fn unit(self) -> Vec3;
 }
 
-pub trait ToColor {
-    fn to_color(self, scale: Vec<f32>) -> RBG;
-}
-
-impl ToColor for Color {
-    fn to_color(self, scale: Vec<f32>) -> RBG {
-        RBG {
-            r: (256. * clamp((self.x * scale[0]).sqrt(), 0.0, 0.999)) as u8,
-            g: (256. * clamp((self.y * scale[1]).sqrt(), 0.0, 0.999)) as u8,
-            b: (256. * clamp((self.z * scale[2]).sqrt(), 0.0, 0.999)) as u8,
-        }
-    }
-}
-
 impl Math for Vec3 {
     fn dot(self, other: Vec3) -> f32 {
         self.x * other.x + self.y * other.y + self.z * other.z
@@ -180,13 +166,3 @@ impl Math for Vec3 {
         self / Vec3::new(self.length())
     }
 }
-
-fn clamp(x: f32, min: f32, max: f32) -> f32 {
-    if x < min {
-        return min;
-    }
-    if x > max {
-        return max;
-    }
-    x
-}
@@ -0,0 +1,11 @@
+use crate::vec3::Point3;
+
+// A scalar field giving how dense a medium is at each point, scaled by the
+// medium's own density.
+pub trait Density {
+    fn density(&self, p: &Point3) -> f32;
+
+    // Upper bound of `density` over all space. Delta tracking samples against
+    // it, so a loose bound costs time and a low one biases the result.
+    fn max_density(&self) -> f32;
+}
@@ -0,0 +1,3 @@
+pub mod density;
+pub mod noise_density;
+pub mod voxel_grid;
@@ -0,0 +1,32 @@
+use crate::textures::perlin::Perlin;
+use crate::vec3::{Point3, Vec3};
+use crate::volumes::density::Density;
+use std::sync::Arc;
+
+// Billowing smoke from a few octaves of Perlin noise, clamped to [0, 1] so
+// that there are gaps between the puffs. `scale` is the frequency, as for the
+// noise textures.
+#[derive(Clone)]
+pub struct NoiseDensity {
+    pub noise: Arc<Perlin>,
+    pub scale: f32,
+}
+
+impl Density for NoiseDensity {
+    fn density(&self, p: &Point3) -> f32 {
+        let p = Vec3::new(self.scale) * *p;
+        let mut sum = 0.;
+        let mut weight = 1.;
+        let mut q = p;
+        for _ in 0..4 {
+            sum += weight * self.noise.noise(&q);
+            weight *= 0.5;
+            q = Vec3::new(2.) * q;
+        }
+        (2. * sum).clamp(0., 1.)
+    }
+
+    fn max_density(&self) -> f32 {
+        1.
+    }
+}
@@ -0,0 +1,139 @@
+use crate::aabb::Aabb;
+use crate::vec3::Point3;
+use crate::volumes::density::Density;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+// Sample type of a raw voxel file. Integers are normalized to [0, 1], all of
+// them little endian.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RawFormat {
+    U8,
+    U16,
+    F32,
+}
+
+impl RawFormat {
+    fn size(self) -> usize {
+        match self {
+            RawFormat::U8 => 1,
+            RawFormat::U16 => 2,
+            RawFormat::F32 => 4,
+        }
+    }
+}
+
+// Dense grid of density samples stretched over `bounds`, x varying fastest
+// and z slowest. Samples sit at cell centers and are blended trilinearly;
+// outside the bounds the density is zero.
+#[derive(Clone)]
+pub struct VoxelGrid {
+    pub bounds: Aabb,
+    resolution: [usize; 3],
+    values: Vec<f32>,
+    max: f32,
+}
+
+impl VoxelGrid {
+    pub fn new(bounds: Aabb, resolution: [usize; 3], values: Vec<f32>) -> Result<Self, String> {
+        if resolution.contains(&0) {
+            return Err(format!(
+                "voxel grid resolution must not be zero, got {:?}",
+                resolution
+            ));
+        }
+        let expected = resolution.iter().product::<usize>();
+        if values.len() != expected {
+            return Err(format!(
+                "voxel grid of {:?} needs {} values, got {}",
+                resolution,
+                expected,
+                values.len()
+            ));
+        }
+        if values.iter().any(|v| !v.is_finite() || *v < 0.) {
+            return Err(String::from(
+                "voxel densities must be finite and not negative",
+            ));
+        }
+
+        let max = values.iter().fold(0f32, |max, v| max.max(*v));
+        Ok(Self {
+            bounds,
+            resolution,
+            values,
+            max,
+        })
+    }
+
+    pub fn load_raw<P: AsRef<Path>>(
+        path: P,
+        bounds: Aabb,
+        resolution: [usize; 3],
+        format: RawFormat,
+    ) -> Result<Self, String> {
+        let bytes = fs::read(&path).map_err(|e| format!("could not read file: {}", e))?;
+        let expected = resolution.iter().product::<usize>() * format.size();
+        if bytes.len() != expected {
+            return Err(format!(
+                "expected {} bytes for {:?} voxels of {} bytes, the file has {}",
+                expected,
+                resolution,
+                format.size(),
+                bytes.len()
+            ));
+        }
+
+        let values = bytes
+            .chunks(format.size())
+            .map(|b| match format {
+                RawFormat::U8 => b[0] as f32 / 255.,
+                RawFormat::U16 => u16::from_le_bytes([b[0], b[1]]) as f32 / 65535.,
+                RawFormat::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            })
+            .collect();
+        Self::new(bounds, resolution, values)
+    }
+
+    fn at(&self, x: usize, y: usize, z: usize) -> f32 {
+        let [nx, ny, _] = self.resolution;
+        self.values[(z * ny + y) * nx + x]
+    }
+}
+
+impl Density for VoxelGrid {
+    fn density(&self, p: &Point3) -> f32 {
+        let min = self.bounds.minimum;
+        let max = self.bounds.maximum;
+        let p = [p.x, p.y, p.z];
+        let lo = [min.x, min.y, min.z];
+        let hi = [max.x, max.y, max.z];
+
+        let mut i0 = [0usize; 3];
+        let mut i1 = [0usize; 3];
+        let mut frac = [0f32; 3];
+        for a in 0..3 {
+            if p[a] < lo[a] || p[a] > hi[a] {
+                return 0.;
+            }
+            let n = self.resolution[a];
+            let x = ((p[a] - lo[a]) / (hi[a] - lo[a]) * n as f32 - 0.5).clamp(0., (n - 1) as f32);
+            let i = (x as usize).min(n - 1);
+            i0[a] = i;
+            i1[a] = (i + 1).min(n - 1);
+            frac[a] = x - i as f32;
+        }
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        // blend along x, then y, then z
+        let along_x = |y, z| lerp(self.at(i0[0], y, z), self.at(i1[0], y, z), frac[0]);
+        let along_y = |z| lerp(along_x(i0[1], z), along_x(i1[1], z), frac[1]);
+        lerp(along_y(i0[2]), along_y(i1[2]), frac[2])
+    }
+
+    fn max_density(&self) -> f32 {
+        self.max
+    }
+}